@@ -0,0 +1,249 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+//! Minimal constant-product AMM used as a local stand-in for the swap program
+//! configured in Subly's `GlobalState.swap_program`. It only implements the
+//! SOL -> token direction required by the treasury rebalancing flow.
+
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+declare_id!("8ANDLSLF5AjVesYNZnSCuPyQfXzNktsbFSrPC7LpZqyR");
+
+pub const POOL_SEED: &str = "pool";
+pub const SOL_RESERVE_SEED: &str = "sol_reserve";
+pub const SWAP_FEE_BPS: u64 = 30; // 0.3%
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_pool(sol_amount, token_amount, &ctx.bumps)
+    }
+
+    pub fn swap_sol_to_token(
+        ctx: Context<SwapSolToToken>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .swap_sol_to_token(amount_in, minimum_amount_out)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub token_mint: Pubkey,
+    pub bump: u8,
+    pub sol_reserve_bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [POOL_SEED.as_bytes(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [SOL_RESERVE_SEED.as_bytes(), pool.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_reserve: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_reserve: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = payer
+    )]
+    pub payer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Account order is part of the swap adapter interface expected by Subly
+#[derive(Accounts)]
+pub struct SwapSolToToken<'info> {
+    #[account(
+        seeds = [POOL_SEED.as_bytes(), pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [SOL_RESERVE_SEED.as_bytes(), pool.key().as_ref()],
+        bump = pool.sol_reserve_bump
+    )]
+    pub pool_sol_reserve: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool.token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_reserve: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_mint
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializePool<'info> {
+    pub fn initialize_pool(
+        &mut self,
+        sol_amount: u64,
+        token_amount: u64,
+        bumps: &InitializePoolBumps,
+    ) -> Result<()> {
+        require!(sol_amount > 0 && token_amount > 0, AmmError::InvalidAmount);
+
+        self.pool.set_inner(Pool {
+            token_mint: self.token_mint.key(),
+            bump: bumps.pool,
+            sol_reserve_bump: bumps.pool_sol_reserve,
+        });
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.pool_sol_reserve.to_account_info(),
+                },
+            ),
+            sol_amount,
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.payer_token_account.to_account_info(),
+                    to: self.pool_token_reserve.to_account_info(),
+                    authority: self.payer.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        msg!(
+            "Pool initialized for mint {} with {} lamports / {} tokens",
+            self.token_mint.key(),
+            sol_amount,
+            token_amount
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> SwapSolToToken<'info> {
+    pub fn swap_sol_to_token(&mut self, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+        require!(amount_in > 0, AmmError::InvalidAmount);
+
+        let amount_out = Self::quote(
+            amount_in,
+            self.pool_sol_reserve.lamports(),
+            self.pool_token_reserve.amount,
+        )?;
+        require!(amount_out >= minimum_amount_out, AmmError::SlippageExceeded);
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trader.to_account_info(),
+                    to: self.pool_sol_reserve.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+
+        let token_mint = self.pool.token_mint;
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.pool_token_reserve.to_account_info(),
+                    to: self.trader_token_account.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                &[&[POOL_SEED.as_bytes(), token_mint.as_ref(), &[self.pool.bump]]],
+            ),
+            amount_out,
+        )?;
+
+        msg!("Swapped {} lamports for {} tokens", amount_in, amount_out);
+
+        Ok(())
+    }
+
+    /// x * y = k output for `amount_in` lamports after the swap fee
+    fn quote(amount_in: u64, sol_reserve: u64, token_reserve: u64) -> Result<u64> {
+        let amount_in_after_fee = (amount_in as u128)
+            .checked_mul((10_000 - SWAP_FEE_BPS) as u128)
+            .ok_or(AmmError::MathOverflow)?
+            / 10_000;
+
+        let numerator = amount_in_after_fee
+            .checked_mul(token_reserve as u128)
+            .ok_or(AmmError::MathOverflow)?;
+        let denominator = (sol_reserve as u128)
+            .checked_add(amount_in_after_fee)
+            .ok_or(AmmError::MathOverflow)?;
+
+        u64::try_from(numerator / denominator).map_err(|_| AmmError::MathOverflow.into())
+    }
+}
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Output below minimum amount")]
+    SlippageExceeded,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -10,12 +10,16 @@ pub const TREASURY_SEED: &str = "treasury";
 // Provider related seeds
 pub const PROVIDER_SEED: &str = "provider";
 pub const SUBSCRIPTION_SERVICE_SEED: &str = "subscription_service";
+pub const PROVIDER_RECORD_SEED: &str = "provider_record";
 
 // User related seeds
 pub const USER_SEED: &str = "user";
 pub const USER_SUBSCRIPTION_SEED: &str = "user_subscription";
 pub const PAYMENT_RECORD_SEED: &str = "payment_record";
+pub const PROTOCOL_FEE_RECORD_SEED: &str = "protocol_fee_record";
 pub const STAKE_ACCOUNT_SEED: &str = "stake_account";
+pub const TRIAL_RECORD_SEED: &str = "trial_record";
+pub const CERTIFICATE_MINT_SEED: &str = "certificate_mint";
 
 // Vault seeds
 pub const SOL_VAULT_SEED: &str = "vault";
@@ -26,11 +30,25 @@ pub const MAX_NAME_LENGTH: usize = 64;
 pub const MAX_DESCRIPTION_LENGTH: usize = 200;
 pub const MAX_URL_LENGTH: usize = 200;
 
+// NFT metadata
+pub const PROVIDER_NFT_SYMBOL: &str = "SUBLYP";
+
 // Protocol configuration
 pub const DEFAULT_PROTOCOL_FEE_BPS: u16 = 100; // 1%
 pub const MAX_PROTOCOL_FEE_BPS: u16 = 1000; // 10%
+pub const MAX_KEEPER_REWARD_BPS: u16 = 10000; // 100% of the protocol fee
+pub const DEFAULT_SWAP_SLIPPAGE_BPS: u16 = 100; // 1%
+pub const MAX_SWAP_SLIPPAGE_BPS: u16 = 1000; // 10%
+pub const DEFAULT_GRACE_PERIOD_DAYS: u64 = 7;
+pub const MAX_GRACE_PERIOD_DAYS: u64 = 30;
+pub const DEFAULT_PAYMENT_RETRY_INTERVAL_HOURS: u64 = 24;
+pub const DEFAULT_PRICE_CHANGE_NOTICE_DAYS: u64 = 30;
+pub const MAX_PRICE_CHANGE_NOTICE_DAYS: u64 = 90;
+pub const SUBSCRIPTION_LOCK_PERIODS: u64 = 12; // Billing periods locked at subscribe time
 pub const MIN_SUBSCRIPTION_PERIOD_DAYS: u64 = 7;
 pub const MAX_SUBSCRIPTION_PERIOD_DAYS: u64 = 365;
+pub const MAX_TRIAL_DAYS: u64 = 90;
+pub const PAYMENT_RECORD_RETENTION_DAYS: i64 = 90; // Records stay on chain at least this long
 
 // Staking configuration
 pub const MIN_STAKE_AMOUNT: u64 = 1_000_000_000; // 1 SOL in lamports
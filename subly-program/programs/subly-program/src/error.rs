@@ -16,6 +16,8 @@ pub enum ErrorCode {
     InvalidFeeAmount,
     #[msg("Invalid billing frequency")]
     InvalidBillingFrequency,
+    #[msg("Invalid trial period")]
+    InvalidTrialPeriod,
     #[msg("Invalid amount")]
     InvalidAmount,
 
@@ -26,14 +28,22 @@ pub enum ErrorCode {
     UnauthorizedAuthority,
     #[msg("Unauthorized provider")]
     UnauthorizedProvider,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Only the rent payer can close this record")]
+    UnauthorizedRentPayer,
 
     // Balance and payment errors
     #[msg("Insufficient balance")]
     InsufficientBalance,
     #[msg("Insufficient available balance (funds locked for subscriptions)")]
     InsufficientAvailableBalance,
+    #[msg("Locked SOL does not match the active subscriptions")]
+    LockedSolMismatch,
     #[msg("Insufficient staked funds")]
     InsufficientStakedFunds,
+    #[msg("Account still holds funds or active subscriptions")]
+    AccountNotEmpty,
 
     // Subscription errors
     #[msg("Invalid subscription ID")]
@@ -44,12 +54,46 @@ pub enum ErrorCode {
     SubscriptionAlreadyExists,
     #[msg("Cannot subscribe to own service")]
     CannotSubscribeToOwnService,
+    #[msg("Subscription is still active")]
+    SubscriptionStillActive,
+    #[msg("Subscription cannot be closed in the second it started")]
+    SubscriptionCloseTooEarly,
+    #[msg("Subscription is cancelled at period end")]
+    SubscriptionCancelled,
+    #[msg("Subscription access period has not ended")]
+    AccessPeriodNotEnded,
+    #[msg("Subscription is paused")]
+    SubscriptionPaused,
+    #[msg("Subscription is not paused")]
+    SubscriptionNotPaused,
+    #[msg("Subscription has a payment due")]
+    PaymentOverdue,
+    #[msg("Subscription is neither cancelled nor on a deactivated service")]
+    SubscriptionNotEnding,
+    #[msg("Subscription is already on this plan")]
+    SamePlan,
+    #[msg("Plan change adjustment has not been settled by a payment yet")]
+    ProrationUnsettled,
 
     // Service errors
     #[msg("Service not found")]
     ServiceNotFound,
     #[msg("Service not active")]
     ServiceNotActive,
+    #[msg("Service already active")]
+    ServiceAlreadyActive,
+    #[msg("Price change does not respect the minimum notice period")]
+    PriceChangeNoticeTooShort,
+    #[msg("Grandfathering is not available for this subscription")]
+    GrandfatheringNotAvailable,
+    #[msg("Provider still has subscribers")]
+    ProviderHasSubscribers,
+    #[msg("Provider still has active services")]
+    ProviderHasLiveServices,
+    #[msg("Provider is already verified")]
+    ProviderAlreadyVerified,
+    #[msg("Provider is not verified")]
+    ProviderNotVerified,
     #[msg("Service limit reached")]
     ServiceLimitReached,
     #[msg("Invalid provider")]
@@ -60,6 +104,14 @@ pub enum ErrorCode {
     // NFT and certificate errors
     #[msg("No certificate to destroy")]
     NoCertificateToDestroy,
+    #[msg("Certificate has not been burned")]
+    CertificateNotBurned,
+    #[msg("Provider NFT does not belong to this provider")]
+    InvalidProviderNft,
+    #[msg("Certificate mint does not belong to this subscription")]
+    InvalidCertificateMint,
+    #[msg("Invalid certificate token account")]
+    InvalidCertificateAccount,
 
     // Price feed errors
     #[msg("Invalid price feed")]
@@ -69,6 +121,32 @@ pub enum ErrorCode {
     #[msg("Invalid price")]
     InvalidPrice,
 
+    // Settlement errors
+    #[msg("Invalid USDC mint")]
+    InvalidUsdcMint,
+    #[msg("Insufficient USDC liquidity in protocol treasury")]
+    InsufficientTreasuryLiquidity,
+    #[msg("Invalid payout address")]
+    InvalidPayoutAddress,
+    #[msg("Payout account does not match the provider payout preference")]
+    InvalidPayoutAccount,
+    #[msg("Token account is frozen")]
+    TokenAccountFrozen,
+    #[msg("Transfer would leave the recipient below rent exemption")]
+    RecipientBelowRentExemption,
+
+    // Swap adapter errors
+    #[msg("Swap program not configured")]
+    SwapNotConfigured,
+    #[msg("Invalid swap program")]
+    InvalidSwapProgram,
+    #[msg("Invalid slippage tolerance")]
+    InvalidSlippage,
+    #[msg("Swap output below minimum")]
+    SlippageExceeded,
+    #[msg("Swap debited more SOL than requested")]
+    SwapInputExceeded,
+
     // Staking errors
     #[msg("Minimum stake amount not met")]
     MinimumStakeNotMet,
@@ -84,14 +162,34 @@ pub enum ErrorCode {
     // Protocol errors
     #[msg("Protocol is paused")]
     ProtocolPaused,
+    #[msg("Protocol is not paused")]
+    ProtocolNotPaused,
     #[msg("Invalid protocol fee")]
     InvalidProtocolFee,
+    #[msg("Invalid keeper reward")]
+    InvalidKeeperReward,
+    #[msg("Invalid dunning configuration")]
+    InvalidDunningConfig,
+    #[msg("Invalid price change notice period")]
+    InvalidPriceChangeNotice,
 
     // Time related errors
     #[msg("Payment not yet due")]
     PaymentNotDue,
     #[msg("Payment already processed")]
     PaymentAlreadyProcessed,
+    #[msg("User vault can cover the payment")]
+    PaymentCoverable,
+    #[msg("Next payment retry not yet allowed")]
+    RetryTooEarly,
+    #[msg("Invalid batch payment accounts")]
+    InvalidBatchAccounts,
+    #[msg("First payment accounts missing or invalid")]
+    InvalidFirstPaymentAccounts,
+    #[msg("Payment record is still within its retention period")]
+    PaymentRecordRetained,
+    #[msg("Payment records do not belong to the same charge")]
+    PaymentRecordMismatch,
 
     // Math errors
     #[msg("Arithmetic overflow")]
@@ -0,0 +1,313 @@
+use crate::state::{PausePolicy, PayoutPreference};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ProtocolFeeUpdated {
+    pub authority: Pubkey,
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceFeedUpdated {
+    pub authority: Pubkey,
+    pub old_price_feed: Pubkey,
+    pub new_price_feed: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakePoolUpdated {
+    pub authority: Pubkey,
+    pub old_stake_pool: Pubkey,
+    pub new_stake_pool: Pubkey,
+    pub old_jito_sol_mint: Pubkey,
+    pub new_jito_sol_mint: Pubkey,
+    pub old_stake_pool_program: Pubkey,
+    pub new_stake_pool_program: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UsdcMintUpdated {
+    pub authority: Pubkey,
+    pub old_usdc_mint: Pubkey,
+    pub new_usdc_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolPaused {
+    pub paused_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolUnpaused {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub authority: Pubkey,
+    pub old_guardian: Option<Pubkey>,
+    pub new_guardian: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct KeeperConfigUpdated {
+    pub authority: Pubkey,
+    pub permissionless_payments: bool,
+    pub keeper_reward_bps: u16,
+    pub keeper_reward_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DunningConfigUpdated {
+    pub authority: Pubkey,
+    pub grace_period_days: u64,
+    pub payment_retry_interval_hours: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceChangeConfigUpdated {
+    pub authority: Pubkey,
+    pub min_price_change_notice_days: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftMetadataConfigUpdated {
+    pub authority: Pubkey,
+    pub nft_metadata_base_uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentFailed {
+    pub keeper: Pubkey,
+    pub user_subscription: Pubkey,
+    pub amount_due_lamports: u64,
+    pub available_lamports: u64,
+    pub failed_payment_attempts: u32,
+    pub past_due_since: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionSuspended {
+    pub user_subscription: Pubkey,
+    pub failed_payment_attempts: u32,
+    pub past_due_since: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct KeeperRewardPaid {
+    pub keeper: Pubkey,
+    pub user_subscription: Pubkey,
+    pub reward_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentRecordsClosed {
+    pub rent_payer: Pubkey,
+    pub user_subscription: Pubkey,
+    pub payment_number: u64,
+    pub payment_date: i64,
+    pub reclaimed_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapConfigUpdated {
+    pub authority: Pubkey,
+    pub old_swap_program: Pubkey,
+    pub new_swap_program: Pubkey,
+    pub max_swap_slippage_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasurySwapped {
+    pub authority: Pubkey,
+    pub swap_program: Pubkey,
+    pub sol_amount: u64,
+    pub usdc_received: u64,
+    pub minimum_usdc_out: u64,
+    pub sol_usd_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderPayoutUpdated {
+    pub provider: Pubkey,
+    pub payout_preference: PayoutPreference,
+    pub payout_address: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionPaused {
+    pub user_subscription: Pubkey,
+    pub lock_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlanChanged {
+    pub user: Pubkey,
+    pub provider: Pubkey,
+    pub from_service_id: u64,
+    pub to_service_id: u64,
+    pub unused_seconds: i64,
+    pub credit_lamports: u64,
+    pub charge_lamports: u64,
+    pub proration_lamports: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionResumed {
+    pub user_subscription: Pubkey,
+    pub paused_seconds: i64,
+    pub next_payment_due: i64,
+    pub lock_restored: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PausePolicyUpdated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub pause_policy: PausePolicy,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionServiceUpdated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionServiceDeactivated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub current_subscribers: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionServiceReactivated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceChangeScheduled {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub current_fee_usd: u64,
+    pub new_fee_usd: u64,
+    pub effective_at: i64,
+    pub allow_grandfathering: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceGrandfathered {
+    pub user_subscription: Pubkey,
+    pub fee_usd: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalStateMigrated {
+    pub authority: Pubkey,
+    pub previous_len: u64,
+    pub new_len: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderServicesMigrated {
+    pub provider: Pubkey,
+    pub legacy_services: u64,
+    pub previous_service_count: u64,
+    pub service_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderUpdated {
+    pub provider: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderDeregistered {
+    pub provider: Pubkey,
+    pub service_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderVerified {
+    pub provider: Pubkey,
+    pub verified_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderVerificationRevoked {
+    pub provider: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierUpdated {
+    pub authority: Pubkey,
+    pub old_verifier: Option<Pubkey>,
+    pub new_verifier: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerificationRequirementUpdated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub requires_verified_provider: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CertificatePolicyUpdated {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub non_transferable_certificates: bool,
+    pub timestamp: i64,
+}
@@ -0,0 +1,425 @@
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    events::*,
+    instructions::{ExecuteSubscriptionPayment, SubscribeToService, UnsubscribeFromService},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{close_account, CloseAccount, Mint, Token2022, TokenAccount},
+};
+
+/// User moves a subscription to another service of the same provider.
+/// The unused part of the current period is credited at the old price and charged at
+/// the new one; the net is settled on the next payment, and the subscription cannot be
+/// cancelled until it has been. The certificate of the old
+/// subscription is burned and a new one is minted under the new subscription, whose
+/// PDA must hold the mint authorities for later burns, freezes and closes.
+#[derive(Accounts)]
+#[instruction(provider: Pubkey, from_service_id: u64, to_service_id: u64)]
+pub struct ChangePlan<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED.as_bytes(), user.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user.key() @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+
+    /// Subscription being replaced; rent goes back to the user
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            &from_service_id.to_le_bytes(),
+        ],
+        bump = current_subscription.bumps,
+        constraint = current_subscription.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = current_subscription.is_active @ ErrorCode::SubscriptionNotActive,
+        constraint = !current_subscription.cancel_at_period_end @ ErrorCode::SubscriptionCancelled,
+        constraint = current_subscription.paused_at.is_none() @ ErrorCode::SubscriptionPaused
+    )]
+    pub current_subscription: Box<Account<'info, UserSubscription>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &from_service_id.to_le_bytes(),
+        ],
+        bump = current_service.bumps
+    )]
+    pub current_service: Box<Account<'info, SubscriptionService>>,
+
+    /// Created on first use, reused when the user held this plan before
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserSubscription::INIT_SPACE,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            &to_service_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub new_subscription: Box<Account<'info, UserSubscription>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &to_service_id.to_le_bytes(),
+        ],
+        bump = new_service.bumps,
+        constraint = new_service.is_active @ ErrorCode::ServiceNotActive
+    )]
+    pub new_service: Box<Account<'info, SubscriptionService>>,
+
+    /// CHECK: Pyth price feed account, validated against GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
+    pub sol_usd_price_feed: AccountInfo<'info>,
+
+    /// Certificate of the current subscription, burned and closed here
+    #[account(
+        mut,
+        address = current_subscription.certificate_mint @ ErrorCode::InvalidCertificateMint
+    )]
+    pub current_certificate_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Whichever account holds the current certificate, required until it has been burned
+    #[account(
+        mut,
+        token::mint = current_certificate_mint,
+        token::token_program = certificate_token_program
+    )]
+    pub current_certificate_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Certificate counter of the target service, shared with `subscribe_to_service`
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TrialRecord::INIT_SPACE,
+        seeds = [
+            TRIAL_RECORD_SEED.as_bytes(),
+            user.key().as_ref(),
+            new_service.key().as_ref()
+        ],
+        bump
+    )]
+    pub trial_record: Box<Account<'info, TrialRecord>>,
+
+    /// Certificate of the new subscription, created and initialised by this instruction
+    /// CHECK: Created and initialised by this instruction at the derived address
+    #[account(
+        mut,
+        seeds = [
+            CERTIFICATE_MINT_SEED.as_bytes(),
+            new_subscription.key().as_ref(),
+            trial_record.certificates_issued.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub certificate_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The user's certificate ATA, created by the associated token program
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &user.key(),
+            &certificate_nft_mint.key(),
+            &certificate_token_program.key()
+        ) @ ErrorCode::InvalidCertificateAccount
+    )]
+    pub certificate_nft_token_account: UncheckedAccount<'info>,
+
+    pub certificate_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ChangePlan<'info> {
+    pub fn change_plan(
+        &mut self,
+        provider: Pubkey,
+        from_service_id: u64,
+        to_service_id: u64,
+        bumps: &ChangePlanBumps,
+    ) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+        require!(from_service_id != to_service_id, ErrorCode::SamePlan);
+
+        // The target plan may only be reused once it has been cancelled and unlocked
+        if self.new_subscription.user != Pubkey::default() {
+            require!(
+                !self.new_subscription.is_active,
+                ErrorCode::SubscriptionAlreadyExists
+            );
+            require!(
+                self.new_subscription.locked_sol == 0,
+                ErrorCode::LockedSolMismatch
+            );
+        }
+
+        // A due payment has to be settled on the current plan first
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time < self.current_subscription.next_payment_due
+                && self.current_subscription.past_due_since.is_none(),
+            ErrorCode::PaymentOverdue
+        );
+        // Payment records are seeded by subscribed_at; a re-created account must not collide
+        require!(
+            current_time > self.current_subscription.subscribed_at,
+            ErrorCode::SubscriptionCloseTooEarly
+        );
+
+        // Unused share of the current period, valued at both prices; a free trial has nothing to prorate
+        let in_trial = self
+            .current_subscription
+            .trial_ends_at
+            .is_some_and(|trial_ends_at| current_time < trial_ends_at);
+        let period_seconds = Self::period_seconds(self.current_service.billing_frequency_days)?;
+        let unused_seconds = if in_trial {
+            0
+        } else {
+            (self.current_subscription.next_payment_due - current_time).min(period_seconds)
+        };
+        // Credit what the current cycle was actually billed at, including grandfathered or scheduled prices
+        let current_fee_usd = ExecuteSubscriptionPayment::fee_for_cycle(
+            &self.current_service,
+            &self.current_subscription,
+            self.current_subscription.next_payment_due - period_seconds,
+        );
+        let credit_usd_cents = Self::prorate(
+            current_fee_usd,
+            unused_seconds,
+            self.current_service.billing_frequency_days,
+        )?;
+        let charge_usd_cents = Self::prorate(
+            self.new_service.fee_usd,
+            unused_seconds,
+            self.new_service.billing_frequency_days,
+        )?;
+
+        let sol_usd_price =
+            ExecuteSubscriptionPayment::get_sol_usd_price_from_pyth(&self.sol_usd_price_feed)?;
+        let credit_lamports = ExecuteSubscriptionPayment::convert_usd_to_sol_lamports(
+            credit_usd_cents,
+            sol_usd_price,
+        )?;
+        let charge_lamports = ExecuteSubscriptionPayment::convert_usd_to_sol_lamports(
+            charge_usd_cents,
+            sol_usd_price,
+        )?;
+        let proration_lamports = (self.current_subscription.proration_lamports as i128)
+            .checked_add(charge_lamports as i128)
+            .and_then(|total| total.checked_sub(credit_lamports as i128))
+            .and_then(|total| i64::try_from(total).ok())
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // Move the lock over to the new plan's price
+        let old_lock = self.current_subscription.locked_sol;
+        self.user_account.locked_sol = self
+            .user_account
+            .locked_sol
+            .checked_sub(old_lock)
+            .ok_or(ErrorCode::LockedSolMismatch)?;
+        let new_lock = ExecuteSubscriptionPayment::convert_usd_to_sol_lamports(
+            self.new_service.fee_usd,
+            sol_usd_price,
+        )?
+        .checked_mul(SUBSCRIPTION_LOCK_PERIODS)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let available_balance = self
+            .user_account
+            .deposited_sol
+            .saturating_sub(self.user_account.locked_sol);
+        require!(
+            available_balance >= new_lock,
+            ErrorCode::InsufficientAvailableBalance
+        );
+        self.user_account.locked_sol = self
+            .user_account
+            .locked_sol
+            .checked_add(new_lock)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // A trial belongs to the service it was granted for, so it ends here and the
+        // new plan is billed right away; otherwise the renewal date carries over
+        let (next_payment_due, trial_ends_at, access_until) = if in_trial {
+            (current_time, Some(current_time), current_time)
+        } else {
+            (
+                self.current_subscription.next_payment_due,
+                self.current_subscription.trial_ends_at,
+                self.current_subscription.access_until,
+            )
+        };
+
+        // The lifetime count of the target account is kept
+        let certificate_index = self.trial_record.certificates_issued;
+        let total_payments_made = self.new_subscription.total_payments_made;
+        self.new_subscription.set_inner(UserSubscription {
+            user: self.user.key(),
+            provider,
+            service_id: to_service_id,
+            subscription_id: to_service_id,
+            subscribed_at: current_time,
+            last_payment_at: self.current_subscription.last_payment_at,
+            next_payment_due,
+            trial_ends_at,
+            total_payments_made,
+            locked_sol: new_lock,
+            is_active: true,
+            cancel_at_period_end: false,
+            access_until,
+            failed_payment_attempts: 0,
+            past_due_since: None,
+            paused_at: None,
+            lock_released_on_pause: 0,
+            grandfathered_fee_usd: None,
+            grandfathered_change_at: 0,
+            proration_lamports,
+            unsubscribed_at: None,
+            certificate_mint: self.certificate_nft_mint.key(),
+            bumps: bumps.new_subscription,
+        });
+        self.current_subscription.locked_sol = 0;
+        self.current_subscription.is_active = false;
+
+        // Only the subscription PDA that minted a certificate can burn, freeze or close it,
+        // so the old one is retired with the old subscription and a new one is issued
+        self.retire_current_certificate()?;
+        self.trial_record.set_inner(TrialRecord {
+            user: self.user.key(),
+            subscription_service: self.new_service.key(),
+            trial_started_at: self.trial_record.trial_started_at,
+            certificates_issued: certificate_index
+                .checked_add(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+            bump: bumps.trial_record,
+        });
+        SubscribeToService::mint_certificate(
+            &self.user,
+            &self.new_subscription,
+            &self.certificate_nft_mint.to_account_info(),
+            &self.certificate_nft_token_account.to_account_info(),
+            &self.certificate_token_program,
+            &self.associated_token_program,
+            &self.system_program,
+            self.new_service.non_transferable_certificates,
+            (certificate_index, bumps.certificate_nft_mint),
+        )?;
+
+        // The user stays with the same provider, so only the service counters move
+        self.current_service.current_subscribers =
+            self.current_service.current_subscribers.saturating_sub(1);
+        self.new_service.current_subscribers = self
+            .new_service
+            .current_subscribers
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(PlanChanged {
+            user: self.user.key(),
+            provider,
+            from_service_id,
+            to_service_id,
+            unused_seconds,
+            credit_lamports,
+            charge_lamports,
+            proration_lamports,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "User {} changed plan {} -> {} with provider {} | credit {} SOL, charge {} SOL, next payment adjustment {} lamports",
+            self.user.key(),
+            from_service_id,
+            to_service_id,
+            provider,
+            credit_lamports as f64 / 1_000_000_000.0,
+            charge_lamports as f64 / 1_000_000_000.0,
+            proration_lamports
+        );
+
+        Ok(())
+    }
+
+    /// Burn the current certificate through the old subscription PDA and return the rent
+    /// of its mint to the user once nothing is left of it
+    fn retire_current_certificate(&mut self) -> Result<()> {
+        UnsubscribeFromService::revoke_certificate(
+            &self.certificate_token_program,
+            &self.current_certificate_mint,
+            self.current_certificate_token_account.as_deref(),
+            &self.current_subscription,
+        )?;
+
+        // Certificates issued before the permanent delegate are frozen, not burned, and stay
+        self.current_certificate_mint.reload()?;
+        if self.current_certificate_mint.supply > 0 {
+            return Ok(());
+        }
+        let service_id_bytes = self.current_subscription.service_id.to_le_bytes();
+        let subscription_seeds: &[&[&[u8]]] = &[&[
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            self.current_subscription.user.as_ref(),
+            self.current_subscription.provider.as_ref(),
+            service_id_bytes.as_ref(),
+            &[self.current_subscription.bumps],
+        ]];
+        close_account(CpiContext::new_with_signer(
+            self.certificate_token_program.to_account_info(),
+            CloseAccount {
+                account: self.current_certificate_mint.to_account_info(),
+                destination: self.user.to_account_info(),
+                authority: self.current_subscription.to_account_info(),
+            },
+            subscription_seeds,
+        ))?;
+
+        msg!(
+            "Closed certificate mint {}",
+            self.current_certificate_mint.key()
+        );
+
+        Ok(())
+    }
+
+    fn period_seconds(billing_frequency_days: u64) -> Result<i64> {
+        (billing_frequency_days as i64)
+            .checked_mul(86400)
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+
+    /// USD cents of `fee_usd` covering `seconds` of a billing period
+    fn prorate(fee_usd: u64, seconds: i64, billing_frequency_days: u64) -> Result<u64> {
+        let period_seconds = Self::period_seconds(billing_frequency_days)?;
+        let cents = (fee_usd as u128)
+            .checked_mul(seconds.max(0) as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(period_seconds as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(u64::try_from(cents).map_err(|_| ErrorCode::ArithmeticOverflow)?)
+    }
+}
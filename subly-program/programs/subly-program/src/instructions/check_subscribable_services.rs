@@ -39,6 +39,7 @@ pub struct SubscribableServiceInfo {
     pub billing_frequency_days: u64,
     pub monthly_fee_sol: u64, // Calculated monthly fee in SOL lamports
     pub can_afford: bool,
+    pub provider_verified: bool,
 }
 
 impl<'info> CheckSubscribableServices<'info> {
@@ -64,8 +65,7 @@ impl<'info> CheckSubscribableServices<'info> {
         // Get user's deposited lamports (available for staking)
         let deposited_lamports = user_account
             .deposited_sol
-            .checked_sub(user_account.locked_sol)
-            .unwrap_or(0);
+            .saturating_sub(user_account.locked_sol);
 
         msg!("User deposited SOL (available): {} lamports", deposited_lamports);
 
@@ -81,19 +81,44 @@ impl<'info> CheckSubscribableServices<'info> {
         let sol_usd_price = Self::get_sol_usd_price_from_pyth(&ctx.accounts.sol_usd_price_feed)?;
         msg!("SOL/USD price from Pyth: ${:.2}", sol_usd_price as f64 / 100.0);
 
-        // Step 3: Process subscription service PDAs from remaining accounts
+        // Step 3: Collect verified providers; their Provider accounts may be passed
+        // next to the services in remaining accounts
+        let mut verified_providers: Vec<Pubkey> = Vec::new();
+
+        for account_info in ctx.remaining_accounts {
+            let data = account_info.data.borrow();
+            if account_info.owner != ctx.program_id || !data.starts_with(Provider::DISCRIMINATOR) {
+                continue;
+            }
+            let provider_account = Provider::try_deserialize(&mut &data[..])?;
+            if provider_account.is_verified {
+                verified_providers.push(provider_account.wallet);
+            }
+        }
+
+        // Step 4: Process subscription service PDAs from remaining accounts
         let mut affordable_services = Vec::new();
         
         for account_info in ctx.remaining_accounts {
-            // Deserialize account data directly
-            let mut data = &account_info.data.borrow()[8..]; // Skip discriminator
-            let service_account = SubscriptionService::try_deserialize(&mut data)?;
+            let data = account_info.data.borrow();
+            if account_info.owner != ctx.program_id
+                || !data.starts_with(SubscriptionService::DISCRIMINATOR)
+            {
+                continue;
+            }
+            let service_account = SubscriptionService::try_deserialize(&mut &data[..])?;
             
             // Skip inactive services
             if !service_account.is_active {
                 continue;
             }
 
+            // Skip services that are only listed once their provider is verified
+            let provider_verified = verified_providers.contains(&service_account.provider);
+            if service_account.requires_verified_provider && !provider_verified {
+                continue;
+            }
+
             // Convert USD fee to SOL lamports using real Pyth price
             let monthly_fee_sol = Self::convert_usd_to_sol_lamports(
                 service_account.fee_usd, 
@@ -112,6 +137,7 @@ impl<'info> CheckSubscribableServices<'info> {
                 billing_frequency_days: service_account.billing_frequency_days,
                 monthly_fee_sol,
                 can_afford,
+                provider_verified,
             };
 
             affordable_services.push(service_info);
@@ -213,7 +239,7 @@ impl<'info> CheckSubscribableServices<'info> {
 
         // Validate reasonable price range ($10 - $1000 per SOL)
         require!(
-            price_cents >= 1000 && price_cents <= 100000,
+            (1000..=100000).contains(&price_cents),
             ErrorCode::InvalidPrice
         );
 
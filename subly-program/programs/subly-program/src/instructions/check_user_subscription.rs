@@ -25,6 +25,16 @@ pub struct CheckUserSubscription<'info> {
         bump,
     )]
     pub user_subscription: Option<Account<'info, UserSubscription>>,
+
+    #[account(
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
 }
 
 impl<'info> CheckUserSubscription<'info> {
@@ -45,9 +55,14 @@ impl<'info> CheckUserSubscription<'info> {
                 ErrorCode::UnauthorizedUser
             );
 
-            // Check if subscription is active
-            let is_active = subscription.is_active;
-            
+            // Active and not paused; once cancelled at period end, or once the service is
+            // deactivated and can no longer renew, only until the paid period ends
+            let will_renew =
+                !subscription.cancel_at_period_end && self.subscription_service.is_active;
+            let is_active = subscription.is_active
+                && subscription.paused_at.is_none()
+                && (will_renew || Clock::get()?.unix_timestamp < subscription.access_until);
+
             msg!(
                 "User {} subscription to provider {} service {}: {}",
                 self.user.key(),
@@ -0,0 +1,69 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Whoever funded a charge's audit records (the keeper, or the user for a first period
+/// collected at subscribe) reclaims their rent once the retention period has passed.
+/// The `Subscription` record and its `ProtocolFee` companion are closed together.
+#[derive(Accounts)]
+pub struct ClosePaymentRecords<'info> {
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        constraint = payment_record.rent_payer == rent_payer.key() @ ErrorCode::UnauthorizedRentPayer,
+        constraint = payment_record.payment_type == PaymentType::Subscription @ ErrorCode::PaymentRecordMismatch
+    )]
+    pub payment_record: Account<'info, PaymentRecord>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        constraint = protocol_fee_record.rent_payer == rent_payer.key() @ ErrorCode::UnauthorizedRentPayer,
+        constraint = protocol_fee_record.payment_type == PaymentType::ProtocolFee @ ErrorCode::PaymentRecordMismatch,
+        constraint = protocol_fee_record.user_subscription == payment_record.user_subscription @ ErrorCode::PaymentRecordMismatch,
+        constraint = protocol_fee_record.payment_number == payment_record.payment_number @ ErrorCode::PaymentRecordMismatch,
+        constraint = protocol_fee_record.payment_date == payment_record.payment_date @ ErrorCode::PaymentRecordMismatch
+    )]
+    pub protocol_fee_record: Account<'info, PaymentRecord>,
+}
+
+impl<'info> ClosePaymentRecords<'info> {
+    pub fn close_payment_records(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let retained_until = PAYMENT_RECORD_RETENTION_DAYS
+            .checked_mul(86400)
+            .and_then(|retention| self.payment_record.payment_date.checked_add(retention))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            current_time >= retained_until,
+            ErrorCode::PaymentRecordRetained
+        );
+
+        let reclaimed_lamports = self
+            .payment_record
+            .get_lamports()
+            .checked_add(self.protocol_fee_record.get_lamports())
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(PaymentRecordsClosed {
+            rent_payer: self.rent_payer.key(),
+            user_subscription: self.payment_record.user_subscription,
+            payment_number: self.payment_record.payment_number,
+            payment_date: self.payment_record.payment_date,
+            reclaimed_lamports,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Payment records #{} of subscription {} closed; {} lamports returned to {}",
+            self.payment_record.payment_number,
+            self.payment_record.user_subscription,
+            reclaimed_lamports,
+            self.rent_payer.key()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{constants::*, error::ErrorCode, state::*};
+use anchor_lang::prelude::*;
+
+/// Reclaim the rent of a fully unstaked stake account
+#[derive(Accounts)]
+pub struct CloseStakeAccount<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [STAKE_ACCOUNT_SEED.as_bytes(), user.key().as_ref()],
+        bump = stake_account.bump,
+        constraint = stake_account.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = !stake_account.is_active @ ErrorCode::AccountNotEmpty,
+        constraint = stake_account.staked_amount == 0 @ ErrorCode::AccountNotEmpty,
+        constraint = stake_account.jito_sol_amount == 0 @ ErrorCode::AccountNotEmpty
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+}
+
+impl<'info> CloseStakeAccount<'info> {
+    pub fn close_stake_account(&mut self) -> Result<()> {
+        msg!(
+            "Closed stake account of user {} (lifetime yield: {} SOL)",
+            self.user.key(),
+            self.stake_account.total_yield_earned as f64 / 1_000_000_000.0
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{constants::*, error::ErrorCode, state::*};
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+/// Reclaim the rent of an empty user account and sweep any dust left in the SOL vault
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [USER_SEED.as_bytes(), user.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = user_account.deposited_sol == 0 @ ErrorCode::AccountNotEmpty,
+        constraint = user_account.locked_sol == 0 @ ErrorCode::AccountNotEmpty,
+        constraint = user_account.staked_sol == 0 @ ErrorCode::AccountNotEmpty,
+        constraint = user_account.active_subscriptions == 0 @ ErrorCode::AccountNotEmpty
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseUser<'info> {
+    pub fn close_user(&mut self, bumps: &CloseUserBumps) -> Result<()> {
+        let user_key = self.user.key();
+        let dust = self.sol_vault.lamports();
+
+        if dust > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.sol_vault.to_account_info(),
+                        to: self.user.to_account_info(),
+                    },
+                    &[&[b"vault", user_key.as_ref(), &[bumps.sol_vault]]],
+                ),
+                dust,
+            )?;
+        }
+
+        msg!(
+            "Closed user account {} ({} SOL swept from vault)",
+            user_key,
+            dust as f64 / 1_000_000_000.0
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{constants::*, error::ErrorCode, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, Token2022, TokenAccount};
+
+/// Reclaim the rent of a cancelled subscription, and optionally of its emptied certificate
+/// token account and burned certificate mint.
+#[derive(Accounts)]
+#[instruction(provider: Pubkey, service_id: u64)]
+pub struct CloseUserSubscription<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            service_id.to_le_bytes().as_ref()
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = !user_subscription.is_active @ ErrorCode::SubscriptionStillActive,
+        constraint = user_subscription.locked_sol == 0 @ ErrorCode::AccountNotEmpty
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+
+    /// Token account of the burned subscription certificate
+    #[account(
+        mut,
+        token::authority = user,
+        token::token_program = token_program,
+        constraint = certificate_nft_token_account.amount == 0 @ ErrorCode::CertificateNotBurned
+    )]
+    pub certificate_nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Burned certificate mint, closed by the subscription PDA as its close authority
+    #[account(
+        mut,
+        address = user_subscription.certificate_mint @ ErrorCode::InvalidCertificateMint,
+        constraint = certificate_nft_mint.supply == 0 @ ErrorCode::CertificateNotBurned
+    )]
+    pub certificate_nft_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> CloseUserSubscription<'info> {
+    pub fn close_user_subscription(&mut self, provider: Pubkey, service_id: u64) -> Result<()> {
+        // A subscription re-created at this address must start at a later timestamp,
+        // otherwise its payment records would collide with the ones already written
+        require!(
+            Clock::get()?.unix_timestamp > self.user_subscription.subscribed_at,
+            ErrorCode::SubscriptionCloseTooEarly
+        );
+
+        if let Some(certificate_nft_token_account) = &self.certificate_nft_token_account {
+            close_account(CpiContext::new(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: certificate_nft_token_account.to_account_info(),
+                    destination: self.user.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ))?;
+
+            msg!(
+                "Closed certificate token account {}",
+                certificate_nft_token_account.key()
+            );
+        }
+
+        if let Some(certificate_nft_mint) = &self.certificate_nft_mint {
+            let user_key = self.user.key();
+            let service_id_bytes = service_id.to_le_bytes();
+            let subscription_seeds: &[&[&[u8]]] = &[&[
+                USER_SUBSCRIPTION_SEED.as_bytes(),
+                user_key.as_ref(),
+                provider.as_ref(),
+                service_id_bytes.as_ref(),
+                &[self.user_subscription.bumps],
+            ]];
+            close_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: certificate_nft_mint.to_account_info(),
+                    destination: self.user.to_account_info(),
+                    authority: self.user_subscription.to_account_info(),
+                },
+                subscription_seeds,
+            ))?;
+
+            msg!("Closed certificate mint {}", certificate_nft_mint.key());
+        }
+
+        msg!(
+            "Closed subscription of user {} to service {} from provider {}",
+            self.user.key(),
+            service_id,
+            provider
+        );
+
+        Ok(())
+    }
+}
@@ -12,7 +12,7 @@ pub struct Deposit<'info> {
     #[account(
         init_if_needed,
         payer = user,
-        space = User::INIT_SPACE,
+        space = 8 + User::INIT_SPACE,
         seeds = [USER_SEED.as_bytes(), user.key().as_ref()],
         bump
     )]
@@ -49,6 +49,7 @@ impl<'info> Deposit<'info> {
             user_account.deposited_sol = 0;
             user_account.locked_sol = 0;
             user_account.staked_sol = 0;
+            user_account.active_subscriptions = 0;
             user_account.created_at = Clock::get()?.unix_timestamp;
             user_account.bump = bumps.user_account;
         }
@@ -0,0 +1,115 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Provider leaves the platform once the last subscriber has run out. Services still
+/// listed are passed in `remaining_accounts` (writable) and deactivated here; every live
+/// service has to be among them. The `Provider` account is closed to the wallet and its
+/// service counter is kept in a `ProviderRecord`, so the deactivated services that stay
+/// behind never have their IDs reused.
+#[derive(Accounts)]
+pub struct DeregisterProvider<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        close = provider,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump = provider_account.bump,
+        constraint = provider_account.wallet == provider.key() @ ErrorCode::UnauthorizedProvider,
+        constraint = provider_account.total_subscribers == 0 @ ErrorCode::ProviderHasSubscribers
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = 8 + ProviderRecord::INIT_SPACE,
+        seeds = [PROVIDER_RECORD_SEED.as_bytes(), provider.key().as_ref()],
+        bump
+    )]
+    pub provider_record: Account<'info, ProviderRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DeregisterProvider<'info> {
+    pub fn deregister_provider(
+        ctx: Context<'_, '_, 'info, 'info, DeregisterProvider<'info>>,
+    ) -> Result<()> {
+        let provider_key = ctx.accounts.provider.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        for service_info in ctx.remaining_accounts.iter() {
+            require!(
+                service_info.is_writable,
+                anchor_lang::error::ErrorCode::ConstraintMut
+            );
+            let mut service = Account::<SubscriptionService>::try_from(service_info)?;
+            require!(
+                service.provider == provider_key,
+                ErrorCode::UnauthorizedProvider
+            );
+            let (expected_address, _) = Pubkey::find_program_address(
+                &[
+                    SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+                    provider_key.as_ref(),
+                    &service.service_id.to_le_bytes(),
+                ],
+                ctx.program_id,
+            );
+            require!(
+                service_info.key() == expected_address,
+                ErrorCode::InvalidServiceId
+            );
+            if !service.is_active {
+                continue;
+            }
+            require!(
+                service.current_subscribers == 0,
+                ErrorCode::ProviderHasSubscribers
+            );
+
+            service.is_active = false;
+            service.exit(ctx.program_id)?;
+            ctx.accounts.provider_account.live_service_count = ctx
+                .accounts
+                .provider_account
+                .live_service_count
+                .saturating_sub(1);
+
+            emit!(SubscriptionServiceDeactivated {
+                provider: provider_key,
+                service_id: service.service_id,
+                current_subscribers: 0,
+                timestamp: current_time,
+            });
+        }
+        require!(
+            ctx.accounts.provider_account.live_service_count == 0,
+            ErrorCode::ProviderHasLiveServices
+        );
+
+        let service_count = ctx.accounts.provider_account.service_count;
+        ctx.accounts.provider_record.set_inner(ProviderRecord {
+            wallet: provider_key,
+            service_count,
+            deregistered_at: current_time,
+            bump: ctx.bumps.provider_record,
+        });
+
+        emit!(ProviderDeregistered {
+            provider: provider_key,
+            service_count,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Provider {} deregistered leaving {} deactivated services",
+            provider_key,
+            service_count
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,480 @@
+use crate::{
+    constants::*, error::ErrorCode, events::*, instructions::ExecuteSubscriptionPayment, state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Number of `remaining_accounts` consumed per subscription in a batch:
+/// user account, user subscription, subscription service, provider account,
+/// user SOL vault, provider payout account (the provider's USDC token account,
+/// or its SOL payout address when the provider is paid in SOL), and the
+/// uninitialized payment record and protocol fee record PDAs for the charge
+pub const PAYMENT_BATCH_GROUP_SIZE: usize = 8;
+
+/// Batched variant of `execute_subscription_payment`
+/// Reads the Pyth price once and charges every due subscription passed in `remaining_accounts`
+#[derive(Accounts)]
+pub struct ExecuteSubscriptionPaymentsBatch<'info> {
+    /// Keeper executing the batch (the protocol authority unless permissionless mode is on)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.permissionless_payments
+            || global_state.authority == keeper.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Treasury collecting the SOL side of every payment
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Protocol's USDC treasury token account paying providers
+    #[account(
+        mut,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = treasury
+    )]
+    pub protocol_usdc_treasury: Account<'info, TokenAccount>,
+
+    /// USDC mint
+    #[account(address = global_state.usdc_mint @ ErrorCode::InvalidUsdcMint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    /// Pyth SOL/USD price feed
+    /// CHECK: Pyth price feed account, must match the one in GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
+    pub sol_usd_price_feed: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SkippedPayment {
+    pub user_subscription: Pubkey,
+    pub error_code: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BatchPaymentSummary {
+    pub processed: u32,
+    pub skipped: u32,
+    pub total_charged_lamports: u64,
+    pub skipped_payments: Vec<SkippedPayment>,
+}
+
+impl<'info> ExecuteSubscriptionPaymentsBatch<'info> {
+    pub fn execute_subscription_payments_batch(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteSubscriptionPaymentsBatch<'info>>,
+    ) -> Result<BatchPaymentSummary> {
+        require!(
+            !ctx.accounts.global_state.is_paused,
+            ErrorCode::ProtocolPaused
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx
+                    .remaining_accounts
+                    .chunks_exact(PAYMENT_BATCH_GROUP_SIZE)
+                    .remainder()
+                    .is_empty(),
+            ErrorCode::InvalidBatchAccounts
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Read the price once for the whole batch
+        let sol_usd_price = ExecuteSubscriptionPayment::get_sol_usd_price_from_pyth(
+            &ctx.accounts.sol_usd_price_feed,
+        )?;
+        msg!(
+            "Batch payment run at {} with SOL/USD price ${:.2}",
+            current_time,
+            sol_usd_price as f64 / 100.0
+        );
+
+        // USDC still available for provider settlement in this batch
+        let mut available_usdc = ctx.accounts.protocol_usdc_treasury.amount;
+
+        let mut summary = BatchPaymentSummary {
+            processed: 0,
+            skipped: 0,
+            total_charged_lamports: 0,
+            skipped_payments: Vec::new(),
+        };
+
+        for group in ctx.remaining_accounts.chunks(PAYMENT_BATCH_GROUP_SIZE) {
+            match ctx.accounts.execute_group(
+                ctx.program_id,
+                ctx.bumps.treasury,
+                group,
+                sol_usd_price,
+                current_time,
+                &mut available_usdc,
+            ) {
+                Ok(charged) => {
+                    summary.processed += 1;
+                    summary.total_charged_lamports = summary
+                        .total_charged_lamports
+                        .checked_add(charged)
+                        .ok_or(ErrorCode::ArithmeticOverflow)?;
+                }
+                Err(err) => {
+                    let error_code = Self::error_code_of(&err);
+                    msg!(
+                        "Skipping subscription {}: error {}",
+                        group[1].key(),
+                        error_code
+                    );
+                    summary.skipped += 1;
+                    summary.skipped_payments.push(SkippedPayment {
+                        user_subscription: group[1].key(),
+                        error_code,
+                    });
+                }
+            }
+        }
+
+        ctx.accounts.global_state.last_payment_processed = current_time;
+
+        msg!(
+            "Batch complete: {} processed, {} skipped, {} lamports charged",
+            summary.processed,
+            summary.skipped,
+            summary.total_charged_lamports
+        );
+
+        Ok(summary)
+    }
+
+    /// Validate and charge one account group, returning the lamports taken from the vault.
+    /// Every check runs before the first transfer so a failing group leaves no partial state.
+    fn execute_group(
+        &self,
+        program_id: &Pubkey,
+        treasury_bump: u8,
+        group: &'info [AccountInfo<'info>],
+        sol_usd_price: u64,
+        current_time: i64,
+        available_usdc: &mut u64,
+    ) -> Result<u64> {
+        let [user_info, subscription_info, service_info, provider_info, vault_info, payout_info, payment_record_info, protocol_fee_record_info] =
+            group
+        else {
+            return err!(ErrorCode::InvalidBatchAccounts);
+        };
+
+        let mut user_account = Account::<User>::try_from(user_info)?;
+        let mut user_subscription = Account::<UserSubscription>::try_from(subscription_info)?;
+        let subscription_service = Account::<SubscriptionService>::try_from(service_info)?;
+        let provider_account = Account::<Provider>::try_from(provider_info)?;
+
+        // Tie the group together
+        require!(
+            user_info.is_writable
+                && subscription_info.is_writable
+                && vault_info.is_writable
+                && payout_info.is_writable
+                && payment_record_info.is_writable
+                && protocol_fee_record_info.is_writable,
+            ErrorCode::InvalidBatchAccounts
+        );
+        require!(
+            user_subscription.user == user_account.wallet,
+            ErrorCode::UnauthorizedUser
+        );
+        require!(
+            user_subscription.provider == subscription_service.provider
+                && provider_account.wallet == subscription_service.provider,
+            ErrorCode::InvalidProvider
+        );
+        require!(
+            user_subscription.service_id == subscription_service.service_id,
+            ErrorCode::InvalidServiceId
+        );
+
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", user_account.wallet.as_ref()], program_id);
+        require_keys_eq!(vault_info.key(), vault_key, ErrorCode::InvalidBatchAccounts);
+        let subscribed_at = user_subscription.subscribed_at.to_le_bytes();
+        let payment_number = user_subscription.total_payments_made.to_le_bytes();
+        let (payment_record_key, payment_record_bump) = Pubkey::find_program_address(
+            &[
+                PAYMENT_RECORD_SEED.as_bytes(),
+                subscription_info.key.as_ref(),
+                &subscribed_at,
+                &payment_number,
+            ],
+            program_id,
+        );
+        require_keys_eq!(
+            payment_record_info.key(),
+            payment_record_key,
+            ErrorCode::InvalidBatchAccounts
+        );
+        let (protocol_fee_record_key, protocol_fee_record_bump) = Pubkey::find_program_address(
+            &[
+                PROTOCOL_FEE_RECORD_SEED.as_bytes(),
+                subscription_info.key.as_ref(),
+                &subscribed_at,
+                &payment_number,
+            ],
+            program_id,
+        );
+        require_keys_eq!(
+            protocol_fee_record_info.key(),
+            protocol_fee_record_key,
+            ErrorCode::InvalidBatchAccounts
+        );
+        // Lamports sent to a record address beforehand are absorbed when it is created
+        require!(
+            payment_record_info.data_is_empty() && protocol_fee_record_info.data_is_empty(),
+            ErrorCode::PaymentAlreadyProcessed
+        );
+
+        let payout_preference = provider_account.payout_preference;
+        let expected_payout = match payout_preference {
+            PayoutPreference::Usdc => {
+                get_associated_token_address(&provider_account.wallet, &self.global_state.usdc_mint)
+            }
+            PayoutPreference::Sol => provider_account.payout_address,
+        };
+        require_keys_eq!(
+            payout_info.key(),
+            expected_payout,
+            ErrorCode::InvalidPayoutAccount
+        );
+
+        // Same business rules as the single payment path
+        require!(
+            user_subscription.is_active,
+            ErrorCode::SubscriptionNotActive
+        );
+        require!(
+            !user_subscription.cancel_at_period_end,
+            ErrorCode::SubscriptionCancelled
+        );
+        require!(
+            user_subscription.paused_at.is_none(),
+            ErrorCode::SubscriptionPaused
+        );
+        require!(subscription_service.is_active, ErrorCode::ServiceNotActive);
+        require!(
+            current_time >= user_subscription.next_payment_due,
+            ErrorCode::PaymentNotDue
+        );
+
+        let quote = ExecuteSubscriptionPayment::quote_payment(
+            &self.global_state,
+            &subscription_service,
+            &user_subscription,
+            payout_preference,
+            self.keeper.key(),
+            sol_usd_price,
+        )?;
+
+        // A CPI failure would abort the whole batch, so every leg is checked up front
+        ExecuteSubscriptionPayment::check_user_can_pay(
+            vault_info,
+            &user_account,
+            &user_subscription,
+            quote.sol_amount,
+        )?;
+        ExecuteSubscriptionPayment::check_can_receive_sol(
+            self.treasury.as_ref(),
+            quote.treasury_amount,
+        )?;
+        match payout_preference {
+            PayoutPreference::Usdc => {
+                // Deserializing also checks the ATA is an initialized token account
+                let provider_usdc_account = Account::<TokenAccount>::try_from(payout_info)?;
+                ExecuteSubscriptionPayment::check_usdc_payout(
+                    &self.protocol_usdc_treasury,
+                    &provider_usdc_account,
+                    *available_usdc,
+                    quote.provider_usdc_amount,
+                )?;
+            }
+            PayoutPreference::Sol => ExecuteSubscriptionPayment::check_can_receive_sol(
+                payout_info,
+                quote.provider_sol_amount,
+            )?,
+        }
+
+        // The keeper funds the rent of both audit records and reclaims it after the retention period
+        let record_rent = Rent::get()?.minimum_balance(8 + PaymentRecord::INIT_SPACE);
+        require!(
+            self.keeper.lamports() >= record_rent.saturating_mul(2),
+            ErrorCode::InsufficientBalance
+        );
+
+        // Persist the audit trail before the payment counter moves
+        let (payment_record, protocol_fee_record) =
+            ExecuteSubscriptionPayment::build_payment_records(
+                &user_subscription,
+                self.keeper.key(),
+                quote.fee_usd,
+                quote.sol_amount,
+                sol_usd_price,
+                quote.protocol_fee,
+                current_time,
+                (payment_record_bump, protocol_fee_record_bump),
+            )?;
+        self.create_payment_record(
+            program_id,
+            payment_record_info,
+            &payment_record,
+            &[
+                PAYMENT_RECORD_SEED.as_bytes(),
+                subscription_info.key.as_ref(),
+                &subscribed_at,
+                &payment_number,
+                &[payment_record_bump],
+            ],
+        )?;
+        self.create_payment_record(
+            program_id,
+            protocol_fee_record_info,
+            &protocol_fee_record,
+            &[
+                PROTOCOL_FEE_RECORD_SEED.as_bytes(),
+                subscription_info.key.as_ref(),
+                &subscribed_at,
+                &payment_number,
+                &[protocol_fee_record_bump],
+            ],
+        )?;
+
+        // Move the funds
+        let vault_seeds: &[&[&[u8]]] = &[&[b"vault", user_account.wallet.as_ref(), &[vault_bump]]];
+        self.transfer_from_vault(
+            vault_info,
+            self.treasury.as_ref(),
+            quote.treasury_amount,
+            vault_seeds,
+        )?;
+        if quote.keeper_reward > 0 {
+            self.transfer_from_vault(
+                vault_info,
+                self.keeper.as_ref(),
+                quote.keeper_reward,
+                vault_seeds,
+            )?;
+            emit!(KeeperRewardPaid {
+                keeper: self.keeper.key(),
+                user_subscription: subscription_info.key(),
+                reward_lamports: quote.keeper_reward,
+                timestamp: current_time,
+            });
+        }
+        match payout_preference {
+            PayoutPreference::Usdc => {
+                ExecuteSubscriptionPayment::transfer_usdc_from_treasury(
+                    &self.token_program,
+                    &self.protocol_usdc_treasury,
+                    &self.usdc_mint,
+                    payout_info,
+                    &self.treasury,
+                    treasury_bump,
+                    quote.provider_usdc_amount,
+                )?;
+                *available_usdc -= quote.provider_usdc_amount;
+            }
+            PayoutPreference::Sol => self.transfer_from_vault(
+                vault_info,
+                payout_info,
+                quote.provider_sol_amount,
+                vault_seeds,
+            )?,
+        }
+
+        // Update and persist state
+        ExecuteSubscriptionPayment::settle_paid_cycle(
+            &mut user_account,
+            &mut user_subscription,
+            &quote,
+            subscription_service.billing_frequency_days,
+            current_time,
+        )?;
+
+        user_subscription.exit(program_id)?;
+        user_account.exit(program_id)?;
+
+        msg!(
+            "PAYMENT EXECUTED: User {} paid {} SOL (${:.2}) to provider {} for service {} | Protocol fee: {} SOL | Provider paid: {} SOL / {} USDC",
+            user_account.wallet,
+            quote.sol_amount as f64 / 1_000_000_000.0,
+            quote.fee_usd as f64 / 100.0,
+            subscription_service.provider,
+            subscription_service.service_id,
+            quote.protocol_fee as f64 / 1_000_000_000.0,
+            quote.provider_sol_amount as f64 / 1_000_000_000.0,
+            quote.provider_usdc_amount as f64 / 1_000_000.0
+        );
+
+        Ok(quote.sol_amount)
+    }
+
+    fn transfer_from_vault(
+        &self,
+        vault: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+        vault_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: vault.clone(),
+                    to: to.clone(),
+                },
+                vault_seeds,
+            ),
+            amount,
+        )
+    }
+
+    /// Allocate a `PaymentRecord` PDA paid for by the keeper and write `record` into it
+    fn create_payment_record(
+        &self,
+        program_id: &Pubkey,
+        record_info: &AccountInfo<'info>,
+        record: &PaymentRecord,
+        record_seeds: &[&[u8]],
+    ) -> Result<()> {
+        ExecuteSubscriptionPayment::create_pda_account(
+            &self.keeper.to_account_info(),
+            record_info,
+            &self.system_program.to_account_info(),
+            8 + PaymentRecord::INIT_SPACE,
+            program_id,
+            &[record_seeds],
+        )?;
+
+        let mut data = record_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        record.try_serialize(&mut writer)
+    }
+
+    /// Numeric error code reported for a skipped group
+    fn error_code_of(err: &Error) -> u32 {
+        match err {
+            Error::AnchorError(anchor_error) => anchor_error.error_code_number,
+            Error::ProgramError(program_error) => {
+                u64::from(program_error.program_error.clone()) as u32
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    instructions::{ExecuteSubscriptionPayment, UnsubscribeFromService},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Keeper instruction that deactivates a subscription cancelled at period end, or
+/// belonging to a deactivated service, once its paid access has expired
+#[derive(Accounts)]
+#[instruction(user: Pubkey, provider: Pubkey, service_id: u64)]
+pub struct FinalizeCancellation<'info> {
+    /// Keeper finalising the cancellation (the protocol authority unless permissionless mode is on)
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.permissionless_payments
+            || global_state.authority == keeper.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED.as_bytes(), user.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.as_ref(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.is_active @ ErrorCode::SubscriptionNotActive
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.as_ref()],
+        bump = provider_account.bump
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    // Certificate of the subscription, burned here if the holder has not done so
+    #[account(
+        mut,
+        address = user_subscription.certificate_mint @ ErrorCode::InvalidCertificateMint
+    )]
+    pub certificate_nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Account holding the certificate, required until it has been burned
+    #[account(
+        mut,
+        token::mint = certificate_nft_mint,
+        token::token_program = token_program
+    )]
+    pub certificate_nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> FinalizeCancellation<'info> {
+    pub fn finalize_cancellation(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            self.user_subscription.cancel_at_period_end || !self.subscription_service.is_active,
+            ErrorCode::SubscriptionNotEnding
+        );
+        require!(
+            current_time >= self.user_subscription.access_until,
+            ErrorCode::AccessPeriodNotEnded
+        );
+
+        // Subscribers of a withdrawn listing never unsubscribed, so their lock is still held
+        let locked_amount = self.user_subscription.locked_sol;
+        ExecuteSubscriptionPayment::release_locked_sol(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            locked_amount,
+        )?;
+
+        UnsubscribeFromService::deactivate_subscription(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            &mut self.subscription_service,
+            &mut self.provider_account,
+            current_time,
+        )?;
+        UnsubscribeFromService::revoke_certificate(
+            &self.token_program,
+            &self.certificate_nft_mint,
+            self.certificate_nft_token_account.as_ref(),
+            &self.user_subscription,
+        )?;
+
+        msg!(
+            "Cancellation finalised for user {} on service {} from provider {} (access ended at {})",
+            self.user_subscription.user,
+            self.user_subscription.service_id,
+            self.user_subscription.provider,
+            self.user_subscription.access_until
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::state::*;
+use crate::{constants::*, state::*};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -51,8 +51,11 @@ impl<'info> Initialize<'info> {
         let global_state = &mut self.global_state;
 
         global_state.authority = self.authority.key();
-        global_state.protocol_fee_bps = 100; // 1% protocol fee
+        global_state.pending_authority = None;
+        global_state.protocol_fee_bps = DEFAULT_PROTOCOL_FEE_BPS; // 1% protocol fee
         global_state.is_paused = false;
+        global_state.guardian = None;
+        global_state.verifier = None;
         
         // Set Jito configuration (can be mainnet or devnet)
         global_state.jito_stake_pool = jito_stake_pool;
@@ -64,10 +67,23 @@ impl<'info> Initialize<'info> {
         
         // Set USDC mint configuration
         global_state.usdc_mint = usdc_mint;
+
+        // Swap adapter is configured later through update_swap_config
+        global_state.swap_program = Pubkey::default();
+        global_state.max_swap_slippage_bps = DEFAULT_SWAP_SLIPPAGE_BPS;
         
         // Initialize counters and timestamps
         global_state.total_services = 0;
         global_state.last_payment_processed = 0;
+
+        // Payments are executed by the authority until permissionless mode is enabled
+        global_state.permissionless_payments = false;
+        global_state.keeper_reward_bps = 0;
+        global_state.keeper_reward_lamports = 0;
+        global_state.grace_period_days = DEFAULT_GRACE_PERIOD_DAYS;
+        global_state.payment_retry_interval_hours = DEFAULT_PAYMENT_RETRY_INTERVAL_HOURS;
+        global_state.min_price_change_notice_days = DEFAULT_PRICE_CHANGE_NOTICE_DAYS;
+        global_state.nft_metadata_base_uri = String::new();
         
         global_state.bump = bumps.global_state;
 
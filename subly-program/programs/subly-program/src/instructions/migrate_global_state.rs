@@ -0,0 +1,76 @@
+use crate::{error::ErrorCode, events::*, instructions::MigrateProviderServices, state::*};
+use anchor_lang::{prelude::*, Discriminator};
+
+/// One-off migration of the `GlobalState` written by the initial release. The account is
+/// read with the frozen legacy layout, grown to the current size at the authority's
+/// expense and rewritten with the defaults `initialize` gives the appended fields.
+/// An account already in the current layout is left untouched.
+#[derive(Accounts)]
+pub struct MigrateGlobalState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Owner, discriminator and authority are verified while decoding the legacy layout
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump
+    )]
+    pub global_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateGlobalState<'info> {
+    pub fn migrate_global_state(&mut self, program_id: &Pubkey) -> Result<()> {
+        let global_state_info = self.global_state.to_account_info();
+        MigrateProviderServices::check_account_type(
+            &global_state_info,
+            program_id,
+            GlobalState::DISCRIMINATOR,
+        )?;
+
+        let previous_len = global_state_info.data_len();
+        let new_len = 8 + GlobalState::INIT_SPACE;
+        let global_state = {
+            let data = global_state_info.try_borrow_data()?;
+            if previous_len < new_len {
+                let legacy = LegacyGlobalState::deserialize(&mut &data[8..])?;
+                GlobalState::from(legacy)
+            } else {
+                GlobalState::try_deserialize(&mut &data[..])?
+            }
+        };
+        require!(
+            global_state.authority == self.authority.key(),
+            ErrorCode::UnauthorizedAuthority
+        );
+        if previous_len >= new_len {
+            msg!("Global state is already in the current layout");
+            return Ok(());
+        }
+
+        MigrateProviderServices::rewrite_account(
+            &self.authority.to_account_info(),
+            &global_state_info,
+            &self.system_program.to_account_info(),
+            new_len,
+            &global_state,
+        )?;
+
+        emit!(GlobalStateMigrated {
+            authority: self.authority.key(),
+            previous_len: previous_len as u64,
+            new_len: new_len as u64,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Global state migrated from {} to {} bytes",
+            previous_len,
+            new_len
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{constants::*, error::ErrorCode, events::*, instructions::RegisterProvider, state::*};
+use anchor_lang::{prelude::*, Discriminator};
+
+/// One-off migration for providers registered before per-provider service IDs.
+/// The `Provider` account and every legacy `SubscriptionService` passed in
+/// `remaining_accounts` (writable) are read with the frozen pre-migration layouts,
+/// grown to the current size at the provider's expense and rewritten in the new
+/// layout. `service_count` moves past the highest legacy ID so new registrations
+/// never land on an existing address. Accounts already in the current layout are
+/// left untouched, so the instruction can be re-run safely.
+#[derive(Accounts)]
+pub struct MigrateProviderServices<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    /// CHECK: Owner, discriminator and wallet are verified while decoding the legacy layout
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump
+    )]
+    pub provider_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateProviderServices<'info> {
+    pub fn migrate_provider_services(
+        ctx: Context<'_, '_, 'info, 'info, MigrateProviderServices<'info>>,
+    ) -> Result<()> {
+        let provider_key = ctx.accounts.provider.key();
+        let provider_info = ctx.accounts.provider_account.to_account_info();
+        let payer = ctx.accounts.provider.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        let (mut provider_account, provider_is_legacy) = {
+            Self::check_account_type(&provider_info, ctx.program_id, Provider::DISCRIMINATOR)?;
+            let data = provider_info.try_borrow_data()?;
+            if provider_info.data_len() < 8 + Provider::INIT_SPACE {
+                let legacy = LegacyProvider::deserialize(&mut &data[8..])?;
+                (legacy.into_provider(0, 0), true)
+            } else {
+                (Provider::try_deserialize(&mut &data[..])?, false)
+            }
+        };
+        require!(
+            provider_account.wallet == provider_key,
+            ErrorCode::UnauthorizedProvider
+        );
+
+        let previous_service_count = provider_account.service_count;
+        let mut service_count = previous_service_count;
+        let mut migrated_services: u64 = 0;
+
+        for service_info in ctx.remaining_accounts.iter() {
+            let subscription_service = {
+                Self::check_account_type(
+                    service_info,
+                    ctx.program_id,
+                    SubscriptionService::DISCRIMINATOR,
+                )?;
+                let data = service_info.try_borrow_data()?;
+                if service_info.data_len() < 8 + SubscriptionService::INIT_SPACE {
+                    let legacy = LegacySubscriptionService::deserialize(&mut &data[8..])?;
+                    Some(SubscriptionService::from(legacy))
+                } else {
+                    None
+                }
+            };
+            let service_id = match &subscription_service {
+                Some(service) => {
+                    require!(
+                        service.provider == provider_key,
+                        ErrorCode::UnauthorizedProvider
+                    );
+                    service.service_id
+                }
+                None => {
+                    let service = Account::<SubscriptionService>::try_from(service_info)?;
+                    require!(
+                        service.provider == provider_key,
+                        ErrorCode::UnauthorizedProvider
+                    );
+                    service.service_id
+                }
+            };
+
+            // Legacy services keep their address, which is still (provider, service_id)
+            let (expected_address, _) = Pubkey::find_program_address(
+                &[
+                    SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+                    provider_key.as_ref(),
+                    &service_id.to_le_bytes(),
+                ],
+                ctx.program_id,
+            );
+            require!(
+                service_info.key() == expected_address,
+                ErrorCode::InvalidServiceId
+            );
+
+            if let Some(service) = subscription_service {
+                // Each legacy service is rewritten once, so it is counted exactly once
+                if service.is_active {
+                    provider_account.live_service_count = provider_account
+                        .live_service_count
+                        .checked_add(1)
+                        .ok_or(ErrorCode::ArithmeticOverflow)?;
+                }
+                Self::rewrite_account(
+                    &payer,
+                    service_info,
+                    &system_program,
+                    8 + SubscriptionService::INIT_SPACE,
+                    &service,
+                )?;
+                migrated_services += 1;
+            }
+
+            service_count = service_count.max(
+                service_id
+                    .checked_add(1)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?,
+            );
+        }
+
+        provider_account.service_count = service_count;
+        if provider_is_legacy {
+            Self::rewrite_account(
+                &payer,
+                &provider_info,
+                &system_program,
+                8 + Provider::INIT_SPACE,
+                &provider_account,
+            )?;
+        } else {
+            provider_account.try_serialize(&mut &mut provider_info.try_borrow_mut_data()?[..])?;
+        }
+
+        emit!(ProviderServicesMigrated {
+            provider: provider_key,
+            legacy_services: migrated_services,
+            previous_service_count,
+            service_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Provider {} migrated {} legacy services; next service ID is {}",
+            provider_key,
+            migrated_services,
+            service_count
+        );
+
+        Ok(())
+    }
+
+    /// Checks the account is owned by this program and carries the expected discriminator
+    pub(crate) fn check_account_type(
+        info: &AccountInfo<'info>,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<()> {
+        require_keys_eq!(
+            *info.owner,
+            *program_id,
+            anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+        );
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && &data[..8] == discriminator,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(())
+    }
+
+    /// Grows a legacy account to `new_len` and writes `account` in the current layout
+    pub(crate) fn rewrite_account<T: AccountSerialize>(
+        payer: &AccountInfo<'info>,
+        info: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        new_len: usize,
+        account: &T,
+    ) -> Result<()> {
+        require!(
+            info.is_writable,
+            anchor_lang::error::ErrorCode::ConstraintMut
+        );
+
+        RegisterProvider::fund_account_growth(
+            payer,
+            info,
+            system_program,
+            new_len.saturating_sub(info.data_len()),
+        )?;
+        info.realloc(new_len, true)?;
+        account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
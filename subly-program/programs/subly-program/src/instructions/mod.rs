@@ -1,27 +1,71 @@
+pub mod change_plan;
 pub mod check_subscribable_services;
 pub mod check_user_subscription;
 pub mod claim_yield;
+pub mod close_payment_records;
+pub mod close_stake_account;
+pub mod close_user;
+pub mod close_user_subscription;
 pub mod deposit;
+pub mod deregister_provider;
+pub mod execute_payments_batch;
+pub mod finalize_cancellation;
 pub mod initialize;
+pub mod migrate_global_state;
+pub mod migrate_provider_services;
+pub mod pause_protocol;
+pub mod pause_subscription;
 pub mod process_payments;
+pub mod record_failed_payment;
 pub mod register_provider;
 pub mod register_subscription_service;
+pub mod schedule_price_change;
 pub mod stake_sol;
 pub mod subscribe_to_service;
+pub mod swap_treasury_sol;
+pub mod transfer_authority;
 pub mod unstake_sol;
 pub mod unsubscribe_from_service;
+pub mod update_config;
+pub mod update_payout_preference;
+pub mod update_provider;
+pub mod update_subscription_service;
+pub mod verify_locked_sol;
+pub mod verify_provider;
 pub mod withdraw;
 
+pub use change_plan::*;
 pub use check_subscribable_services::*;
 pub use check_user_subscription::*;
 pub use claim_yield::*;
+pub use close_payment_records::*;
+pub use close_stake_account::*;
+pub use close_user::*;
+pub use close_user_subscription::*;
 pub use deposit::*;
+pub use deregister_provider::*;
+pub use execute_payments_batch::*;
+pub use finalize_cancellation::*;
 pub use initialize::*;
+pub use migrate_global_state::*;
+pub use migrate_provider_services::*;
+pub use pause_protocol::*;
+pub use pause_subscription::*;
 pub use process_payments::*;
+pub use record_failed_payment::*;
 pub use register_provider::*;
 pub use register_subscription_service::*;
+pub use schedule_price_change::*;
 pub use stake_sol::*;
 pub use subscribe_to_service::*;
+pub use swap_treasury_sol::*;
+pub use transfer_authority::*;
 pub use unstake_sol::*;
 pub use unsubscribe_from_service::*;
+pub use update_config::*;
+pub use update_payout_preference::*;
+pub use update_provider::*;
+pub use update_subscription_service::*;
+pub use verify_locked_sol::*;
+pub use verify_provider::*;
 pub use withdraw::*;
@@ -0,0 +1,102 @@
+use crate::{error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Circuit breaker: the authority or the guardian can halt the protocol
+#[derive(Accounts)]
+pub struct PauseProtocol<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == signer.key()
+            || global_state.guardian == Some(signer.key()) @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Only the authority can resume the protocol, the guardian can never unpause
+#[derive(Accounts)]
+pub struct UnpauseProtocol<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Authority-only instruction to set or clear the pause-only guardian key
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+impl<'info> PauseProtocol<'info> {
+    pub fn pause_protocol(&mut self) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+
+        self.global_state.is_paused = true;
+
+        emit!(ProtocolPaused {
+            paused_by: self.signer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Withdrawals intentionally stay open so users can always exit
+        msg!("Subly protocol paused by {}", self.signer.key());
+
+        Ok(())
+    }
+}
+
+impl<'info> UnpauseProtocol<'info> {
+    pub fn unpause_protocol(&mut self) -> Result<()> {
+        require!(self.global_state.is_paused, ErrorCode::ProtocolNotPaused);
+
+        self.global_state.is_paused = false;
+
+        emit!(ProtocolUnpaused {
+            authority: self.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Subly protocol unpaused by {}", self.authority.key());
+
+        Ok(())
+    }
+}
+
+impl<'info> SetGuardian<'info> {
+    pub fn set_guardian(&mut self, guardian: Option<Pubkey>) -> Result<()> {
+        let old_guardian = self.global_state.guardian;
+        self.global_state.guardian = guardian;
+
+        emit!(GuardianUpdated {
+            authority: self.authority.key(),
+            old_guardian,
+            new_guardian: guardian,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Protocol guardian updated: {:?} -> {:?}",
+            old_guardian,
+            guardian
+        );
+
+        Ok(())
+    }
+}
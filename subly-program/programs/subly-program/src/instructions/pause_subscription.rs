@@ -0,0 +1,241 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// User freezes the billing clock of one subscription, keeping its slot and certificate
+#[derive(Accounts)]
+#[instruction(provider: Pubkey, service_id: u64)]
+pub struct PauseSubscription<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED.as_bytes(), user.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user.key() @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = user_subscription.is_active @ ErrorCode::SubscriptionNotActive,
+        constraint = !user_subscription.cancel_at_period_end @ ErrorCode::SubscriptionCancelled,
+        constraint = user_subscription.paused_at.is_none() @ ErrorCode::SubscriptionPaused
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+
+    /// Service whose pause policy decides what happens to the locked SOL
+    #[account(
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+/// User restarts a paused subscription; the billing schedule shifts by the paused duration
+#[derive(Accounts)]
+#[instruction(provider: Pubkey, service_id: u64)]
+pub struct ResumeSubscription<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED.as_bytes(), user.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user.key() @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = user_subscription.is_active @ ErrorCode::SubscriptionNotActive
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+}
+
+/// Provider chooses whether pausing a subscription keeps or releases its locked SOL
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct UpdatePausePolicy<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+impl<'info> PauseSubscription<'info> {
+    pub fn pause_subscription(&mut self) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+
+        // A due or past-due payment has to be settled before pausing
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time < self.user_subscription.next_payment_due
+                && self.user_subscription.past_due_since.is_none(),
+            ErrorCode::PaymentOverdue
+        );
+
+        let lock_released = match self.subscription_service.pause_policy {
+            PausePolicy::KeepLock => 0,
+            PausePolicy::ReleaseLock => {
+                let locked_amount = self.user_subscription.locked_sol;
+                self.user_account.locked_sol = self
+                    .user_account
+                    .locked_sol
+                    .checked_sub(locked_amount)
+                    .ok_or(ErrorCode::LockedSolMismatch)?;
+                self.user_subscription.locked_sol = 0;
+                locked_amount
+            }
+        };
+
+        self.user_subscription.paused_at = Some(current_time);
+        self.user_subscription.lock_released_on_pause = lock_released;
+
+        emit!(SubscriptionPaused {
+            user_subscription: self.user_subscription.key(),
+            lock_released,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Subscription {} paused ({} SOL released)",
+            self.user_subscription.key(),
+            lock_released as f64 / 1_000_000_000.0
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> ResumeSubscription<'info> {
+    pub fn resume_subscription(&mut self) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+
+        let paused_at = self
+            .user_subscription
+            .paused_at
+            .ok_or(ErrorCode::SubscriptionNotPaused)?;
+        let current_time = Clock::get()?.unix_timestamp;
+        let paused_seconds = current_time.saturating_sub(paused_at);
+
+        // Re-lock what the pause released, out of the user's free balance
+        let lock_restored = self.user_subscription.lock_released_on_pause;
+        if lock_restored > 0 {
+            let available_balance = self
+                .user_account
+                .deposited_sol
+                .saturating_sub(self.user_account.locked_sol);
+            require!(
+                available_balance >= lock_restored,
+                ErrorCode::InsufficientAvailableBalance
+            );
+
+            self.user_account.locked_sol = self
+                .user_account
+                .locked_sol
+                .checked_add(lock_restored)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            self.user_subscription.locked_sol = self
+                .user_subscription
+                .locked_sol
+                .checked_add(lock_restored)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+
+        // The paused time is not billed: shift the schedule forward by it
+        let user_subscription = &mut self.user_subscription;
+        user_subscription.next_payment_due = user_subscription
+            .next_payment_due
+            .checked_add(paused_seconds)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        user_subscription.access_until = user_subscription
+            .access_until
+            .checked_add(paused_seconds)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        user_subscription.paused_at = None;
+        user_subscription.lock_released_on_pause = 0;
+
+        emit!(SubscriptionResumed {
+            user_subscription: user_subscription.key(),
+            paused_seconds,
+            next_payment_due: user_subscription.next_payment_due,
+            lock_restored,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Subscription {} resumed after {} seconds, next payment due at {}",
+            user_subscription.key(),
+            paused_seconds,
+            user_subscription.next_payment_due
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> UpdatePausePolicy<'info> {
+    pub fn update_pause_policy(
+        &mut self,
+        service_id: u64,
+        pause_policy: PausePolicy,
+    ) -> Result<()> {
+        self.subscription_service.pause_policy = pause_policy;
+
+        emit!(PausePolicyUpdated {
+            provider: self.provider.key(),
+            service_id,
+            pause_policy,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Pause policy of service {} set to {:?}",
+            service_id,
+            pause_policy
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
-use crate::{constants::*, error::ErrorCode, state::*};
-use anchor_lang::prelude::*;
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked},
 };
 use pyth_sdk_solana::state::SolanaPriceAccount;
 
@@ -30,12 +35,13 @@ pub struct ProcessSubscriptionPayments<'info> {
     pub treasury: SystemAccount<'info>,
 
     /// Pyth SOL/USD price feed account
-    /// CHECK: Pyth price feed account
+    /// CHECK: Pyth price feed account, must match the one in GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
     pub sol_usd_price_feed: AccountInfo<'info>,
 
     /// USDC mint account
     #[account(
-        constraint = usdc_mint.key() == global_state.usdc_mint @ ErrorCode::InvalidPriceFeed
+        constraint = usdc_mint.key() == global_state.usdc_mint @ ErrorCode::InvalidUsdcMint
     )]
     pub usdc_mint: Account<'info, Mint>,
 
@@ -53,18 +59,20 @@ pub struct ProcessSubscriptionPayments<'info> {
 }
 
 /// Individual payment execution instruction (Pay Subscription Fee 2)
-/// This is called for each user whose payment is due, handling the complete payment flow
+/// This is called for each user whose payment is due, handling the complete payment flow.
+/// In permissionless mode any keeper may crank it and is tipped from the protocol fee.
 #[derive(Accounts)]
 #[instruction(user: Pubkey, provider: Pubkey, service_id: u64)]
 pub struct ExecuteSubscriptionPayment<'info> {
-    /// Protocol authority executing the payment
+    /// Keeper executing the payment (the protocol authority unless permissionless mode is on)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub keeper: Signer<'info>,
 
     #[account(
         seeds = [b"global_state"],
         bump = global_state.bump,
-        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+        constraint = global_state.permissionless_payments
+            || global_state.authority == keeper.key() @ ErrorCode::UnauthorizedAuthority
     )]
     pub global_state: Account<'info, GlobalState>,
 
@@ -125,15 +133,22 @@ pub struct ExecuteSubscriptionPayment<'info> {
     )]
     pub user_sol_vault: SystemAccount<'info>,
 
-    /// Provider's USDC account for receiving payments
+    /// Provider's USDC account, required when the provider is paid in USDC
     #[account(
         mut,
         associated_token::mint = usdc_mint,
         associated_token::authority = provider
     )]
-    pub provider_usdc_account: Account<'info, TokenAccount>,
+    pub provider_usdc_account: Option<Account<'info, TokenAccount>>,
+
+    /// Provider's SOL payout address, required when the provider is paid in SOL
+    #[account(
+        mut,
+        address = provider_account.payout_address @ ErrorCode::InvalidPayoutAccount
+    )]
+    pub provider_sol_payout: Option<SystemAccount<'info>>,
 
-    /// Protocol's USDC treasury token account
+    /// Protocol treasury, collects SOL and owns the USDC inventory used for settlement
     #[account(
         mut,
         seeds = [b"treasury"],
@@ -141,18 +156,71 @@ pub struct ExecuteSubscriptionPayment<'info> {
     )]
     pub treasury: SystemAccount<'info>,
 
+    /// Protocol's USDC treasury token account paying providers
+    #[account(
+        mut,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = treasury
+    )]
+    pub protocol_usdc_treasury: Account<'info, TokenAccount>,
+
     /// USDC mint
+    #[account(address = global_state.usdc_mint @ ErrorCode::InvalidUsdcMint)]
     pub usdc_mint: Account<'info, Mint>,
 
     /// Pyth SOL/USD price feed
-    /// CHECK: Pyth price feed account
+    /// CHECK: Pyth price feed account, must match the one in GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
     pub sol_usd_price_feed: AccountInfo<'info>,
 
+    /// Audit record of this charge, indexed by the subscription's start time and payment count
+    /// (the start time keeps a closed and re-created subscription from reusing record addresses)
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + PaymentRecord::INIT_SPACE,
+        seeds = [
+            PAYMENT_RECORD_SEED.as_bytes(),
+            user_subscription.key().as_ref(),
+            &user_subscription.subscribed_at.to_le_bytes(),
+            &user_subscription.total_payments_made.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub payment_record: Box<Account<'info, PaymentRecord>>,
+
+    /// Companion record of the protocol fee taken from this charge
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + PaymentRecord::INIT_SPACE,
+        seeds = [
+            PROTOCOL_FEE_RECORD_SEED.as_bytes(),
+            user_subscription.key().as_ref(),
+            &user_subscription.subscribed_at.to_le_bytes(),
+            &user_subscription.total_payments_made.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub protocol_fee_record: Box<Account<'info, PaymentRecord>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+/// Amounts moved by one renewal charge, shared by the single and batched payment paths
+pub(crate) struct PaymentQuote {
+    pub fee_usd: u64, // USD cents of the cycle
+    pub sol_amount: u64, // Lamports taken from the user vault, after proration
+    pub proration_carry: i64, // Credit left for later cycles
+    pub protocol_fee: u64,
+    pub keeper_reward: u64, // Part of the protocol fee paid to the keeper
+    pub provider_sol_amount: u64,
+    pub provider_usdc_amount: u64,
+    pub treasury_amount: u64, // Lamports sent from the vault to the treasury
+}
+
 impl<'info> ProcessSubscriptionPayments<'info> {
     /// Main entry point for daily batch processing of subscription payments
     /// Implements "Pay Subscription Fee 1" flow from the diagram
@@ -234,7 +302,7 @@ impl<'info> ProcessSubscriptionPayments<'info> {
 
         // Validate reasonable SOL price range ($10 - $1000)
         require!(
-            price_cents >= 1000 && price_cents <= 100000,
+            (1000..=100000).contains(&price_cents),
             ErrorCode::InvalidPrice
         );
 
@@ -257,11 +325,19 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
             ErrorCode::PaymentNotDue
         );
 
-        // 3. Verify subscription is still active
+        // 3. Verify subscription is still active and not cancelled at period end
         require!(
             self.user_subscription.is_active,
             ErrorCode::SubscriptionNotActive
         );
+        require!(
+            !self.user_subscription.cancel_at_period_end,
+            ErrorCode::SubscriptionCancelled
+        );
+        require!(
+            self.user_subscription.paused_at.is_none(),
+            ErrorCode::SubscriptionPaused
+        );
 
         // 4. Verify service is still active
         require!(
@@ -276,77 +352,353 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
             sol_usd_price as f64 / 100.0
         );
 
-        // 6. Calculate payment amounts
-        let fee_usd = self.subscription_service.fee_usd; // in cents
+        // 6. Price the charge: cycle fee, proration, protocol fee, keeper tip and payout split
+        let payout_preference = self.provider_account.payout_preference;
+        let quote = Self::quote_payment(
+            &self.global_state,
+            &self.subscription_service,
+            &self.user_subscription,
+            payout_preference,
+            self.keeper.key(),
+            sol_usd_price,
+        )?;
         let billing_frequency_days = self.subscription_service.billing_frequency_days;
 
-        // 7. Convert USD fee to SOL lamports using real-time price
-        let sol_amount_needed = Self::convert_usd_to_sol_lamports(fee_usd, sol_usd_price)?;
+        // 7. Verify every leg can settle before any funds move
+        Self::check_user_can_pay(
+            self.user_sol_vault.as_ref(),
+            &self.user_account,
+            &self.user_subscription,
+            quote.sol_amount,
+        )?;
+        Self::check_can_receive_sol(self.treasury.as_ref(), quote.treasury_amount)?;
+        match payout_preference {
+            PayoutPreference::Usdc => {
+                let provider_usdc_account = self
+                    .provider_usdc_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidPayoutAccount)?;
+                Self::check_usdc_payout(
+                    &self.protocol_usdc_treasury,
+                    provider_usdc_account,
+                    self.protocol_usdc_treasury.amount,
+                    quote.provider_usdc_amount,
+                )?;
+            }
+            PayoutPreference::Sol => {
+                let provider_sol_payout = self
+                    .provider_sol_payout
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidPayoutAccount)?;
+                Self::check_can_receive_sol(
+                    provider_sol_payout.as_ref(),
+                    quote.provider_sol_amount,
+                )?;
+            }
+        }
+
+        // 8. Execute SOL transfers from user vault
+        // SOL-paid providers are paid from the vault, so only the protocol fee reaches the treasury
+        self.transfer_sol_from_user_vault(quote.treasury_amount, bumps)?;
+        self.pay_keeper_reward(quote.keeper_reward, current_time, bumps)?;
+
+        // 9. Pay the provider: SOL from the user vault or USDC from the treasury inventory
+        match payout_preference {
+            PayoutPreference::Usdc => {
+                self.transfer_usdc_to_provider(quote.provider_usdc_amount, bumps)?
+            }
+            PayoutPreference::Sol => {
+                self.transfer_sol_to_provider(quote.provider_sol_amount, bumps)?
+            }
+        }
+
+        // 10. Persist the audit trail before the payment counter moves
+        let (payment_record, protocol_fee_record) = Self::build_payment_records(
+            &self.user_subscription,
+            self.keeper.key(),
+            quote.fee_usd,
+            quote.sol_amount,
+            sol_usd_price,
+            quote.protocol_fee,
+            current_time,
+            (bumps.payment_record, bumps.protocol_fee_record),
+        )?;
+        self.payment_record.set_inner(payment_record);
+        self.protocol_fee_record.set_inner(protocol_fee_record);
+
+        // 11. Handle subscription certificate (burn if final payment or update)
+        self.handle_subscription_certificate(current_time, bumps)?;
+
+        // 12. Advance the subscription and settle the user's balances
+        Self::settle_paid_cycle(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            &quote,
+            billing_frequency_days,
+            current_time,
+        )?;
+
+        // 13. Log successful payment
+        msg!(
+            "PAYMENT EXECUTED: User {} paid {} SOL (${:.2}) to provider {} for service {} | Protocol fee: {} SOL | Next due: {}",
+            self.user_account.wallet,
+            quote.sol_amount as f64 / 1_000_000_000.0,
+            quote.fee_usd as f64 / 100.0,
+            self.subscription_service.provider,
+            self.subscription_service.service_id,
+            quote.protocol_fee as f64 / 1_000_000_000.0,
+            self.user_subscription.next_payment_due
+        );
+
+        Ok(())
+    }
+
+    /// Transfer SOL from user vault to treasury for conversion
+    fn transfer_sol_from_user_vault(
+        &mut self,
+        amount: u64,
+        bumps: &ExecuteSubscriptionPaymentBumps,
+    ) -> Result<()> {
+        let user_vault_bump = bumps.user_sol_vault;
+        let user_key = self.user_account.wallet;
+
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: self.user_sol_vault.to_account_info(),
+            to: self.treasury.to_account_info(),
+        };
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                transfer_ix,
+                &[&[b"vault", user_key.as_ref(), &[user_vault_bump]]],
+            ),
+            amount,
+        )?;
+
+        msg!(
+            "Transferred {} SOL from user vault to treasury",
+            amount as f64 / 1_000_000_000.0
+        );
+        Ok(())
+    }
+
+    /// Price the charge of the cycle starting at `next_payment_due` and split it between
+    /// the treasury, the keeper and the provider
+    pub(crate) fn quote_payment(
+        global_state: &GlobalState,
+        subscription_service: &SubscriptionService,
+        user_subscription: &UserSubscription,
+        payout_preference: PayoutPreference,
+        keeper: Pubkey,
+        sol_usd_price: u64,
+    ) -> Result<PaymentQuote> {
+        let fee_usd = Self::fee_for_cycle(
+            subscription_service,
+            user_subscription,
+            user_subscription.next_payment_due,
+        ); // in cents
+
+        // Convert the USD fee at the real-time price, then settle any plan-change proration
+        let (sol_amount, proration_carry) = Self::apply_proration(
+            Self::convert_usd_to_sol_lamports(fee_usd, sol_usd_price)?,
+            user_subscription.proration_lamports,
+        )?;
 
-        // 8. Verify user has sufficient funds
+        let (protocol_fee, provider_payment_amount) =
+            Self::split_protocol_fee(sol_amount, global_state.protocol_fee_bps)?;
+        // The keeper tip is carved out of the protocol fee
+        let keeper_reward = Self::calculate_keeper_reward(global_state, keeper, protocol_fee)?;
+
+        // Size the provider payout in the currency the provider asked for
+        let (provider_sol_amount, provider_usdc_amount) = match payout_preference {
+            PayoutPreference::Usdc => (
+                0,
+                Self::convert_sol_to_usdc_amount(provider_payment_amount, sol_usd_price)?,
+            ),
+            PayoutPreference::Sol => (provider_payment_amount, 0),
+        };
+        let treasury_amount = sol_amount
+            .checked_sub(keeper_reward)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?
+            .checked_sub(provider_sol_amount)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        Ok(PaymentQuote {
+            fee_usd,
+            sol_amount,
+            proration_carry,
+            protocol_fee,
+            keeper_reward,
+            provider_sol_amount,
+            provider_usdc_amount,
+            treasury_amount,
+        })
+    }
+
+    /// The vault must either stay rent-exempt after paying `amount` or be drained exactly,
+    /// and the deposit and lock bookkeeping must cover the charge
+    pub(crate) fn check_user_can_pay(
+        user_sol_vault: &AccountInfo<'info>,
+        user_account: &User,
+        user_subscription: &UserSubscription,
+        amount: u64,
+    ) -> Result<()> {
+        let remaining = user_sol_vault
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientBalance)?;
         require!(
-            self.user_sol_vault.lamports() >= sol_amount_needed,
+            remaining == 0
+                || remaining >= Rent::get()?.minimum_balance(user_sol_vault.data_len()),
             ErrorCode::InsufficientBalance
         );
+        require!(
+            user_account.deposited_sol >= amount,
+            ErrorCode::InsufficientBalance
+        );
+        require!(
+            user_account.locked_sol >= user_subscription.locked_sol.min(amount),
+            ErrorCode::LockedSolMismatch
+        );
+
+        Ok(())
+    }
+
+    /// A SOL transfer may not leave its recipient below rent exemption
+    pub(crate) fn check_can_receive_sol(recipient: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let balance = recipient
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            balance >= Rent::get()?.minimum_balance(recipient.data_len()),
+            ErrorCode::RecipientBelowRentExemption
+        );
+
+        Ok(())
+    }
 
-        // 9. Calculate protocol fee
-        let protocol_fee_bps = self.global_state.protocol_fee_bps;
-        let protocol_fee_amount = sol_amount_needed
+    /// Both USDC accounts of a payout must be usable and the inventory deep enough
+    pub(crate) fn check_usdc_payout(
+        protocol_usdc_treasury: &TokenAccount,
+        provider_usdc_account: &TokenAccount,
+        available_usdc: u64,
+        usdc_amount: u64,
+    ) -> Result<()> {
+        require!(
+            !protocol_usdc_treasury.is_frozen() && !provider_usdc_account.is_frozen(),
+            ErrorCode::TokenAccountFrozen
+        );
+        require!(
+            available_usdc >= usdc_amount,
+            ErrorCode::InsufficientTreasuryLiquidity
+        );
+
+        Ok(())
+    }
+
+    /// Split a payment into (protocol fee, provider share)
+    pub(crate) fn split_protocol_fee(sol_amount: u64, protocol_fee_bps: u16) -> Result<(u64, u64)> {
+        let protocol_fee_amount = sol_amount
             .checked_mul(protocol_fee_bps as u64)
             .ok_or(ErrorCode::ArithmeticOverflow)?
             .checked_div(10000)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        let provider_payment_amount = sol_amount_needed
+        let provider_payment_amount = sol_amount
             .checked_sub(protocol_fee_amount)
             .ok_or(ErrorCode::ArithmeticUnderflow)?;
 
-        // 10. Execute SOL transfers from user vault
-        self.transfer_sol_from_user_vault(sol_amount_needed, bumps)?;
+        Ok((protocol_fee_amount, provider_payment_amount))
+    }
 
-        // 11. Convert SOL to USDC and pay provider
-        let usdc_amount_for_provider =
-            Self::convert_sol_to_usdc_amount(provider_payment_amount, sol_usd_price)?;
+    /// Keeper tip: a share of the protocol fee plus a flat amount, never more than the fee itself
+    pub(crate) fn calculate_keeper_reward(
+        global_state: &GlobalState,
+        keeper: Pubkey,
+        protocol_fee_amount: u64,
+    ) -> Result<u64> {
+        // The protocol authority cranking its own payments is not tipped
+        if keeper == global_state.authority {
+            return Ok(0);
+        }
+
+        let bps_reward = protocol_fee_amount
+            .checked_mul(global_state.keeper_reward_bps as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        // 12. Transfer USDC to provider
-        self.transfer_usdc_to_provider(usdc_amount_for_provider, bumps)?;
+        let reward = bps_reward
+            .checked_add(global_state.keeper_reward_lamports)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        // 13. Handle subscription certificate (burn if final payment or update)
-        self.handle_subscription_certificate(current_time, bumps)?;
+        Ok(reward.min(protocol_fee_amount))
+    }
 
-        // 14. Update subscription state
-        self.update_subscription_after_payment(billing_frequency_days, current_time)?;
+    /// Transfer the keeper tip straight from the user vault to the keeper
+    fn pay_keeper_reward(
+        &mut self,
+        amount: u64,
+        current_time: i64,
+        bumps: &ExecuteSubscriptionPaymentBumps,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let user_vault_bump = bumps.user_sol_vault;
+        let user_key = self.user_account.wallet;
 
-        // 15. Update user account balances
-        self.update_user_balances(sol_amount_needed)?;
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: self.user_sol_vault.to_account_info(),
+            to: self.keeper.to_account_info(),
+        };
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                transfer_ix,
+                &[&[b"vault", user_key.as_ref(), &[user_vault_bump]]],
+            ),
+            amount,
+        )?;
+
+        emit!(KeeperRewardPaid {
+            keeper: self.keeper.key(),
+            user_subscription: self.user_subscription.key(),
+            reward_lamports: amount,
+            timestamp: current_time,
+        });
 
-        // 16. Log successful payment
         msg!(
-            "PAYMENT EXECUTED: User {} paid {} SOL (${:.2}) to provider {} for service {} | Protocol fee: {} SOL | Next due: {}",
-            self.user_account.wallet,
-            sol_amount_needed as f64 / 1_000_000_000.0,
-            fee_usd as f64 / 100.0,
-            self.subscription_service.provider,
-            self.subscription_service.service_id,
-            protocol_fee_amount as f64 / 1_000_000_000.0,
-            self.user_subscription.next_payment_due
+            "Keeper {} rewarded {} SOL",
+            self.keeper.key(),
+            amount as f64 / 1_000_000_000.0
         );
-
         Ok(())
     }
 
-    /// Transfer SOL from user vault to treasury for conversion
-    fn transfer_sol_from_user_vault(
+    /// Transfer the provider share in SOL from the user vault to the provider payout address
+    fn transfer_sol_to_provider(
         &mut self,
         amount: u64,
         bumps: &ExecuteSubscriptionPaymentBumps,
     ) -> Result<()> {
+        let provider_sol_payout = self
+            .provider_sol_payout
+            .as_ref()
+            .ok_or(ErrorCode::InvalidPayoutAccount)?;
         let user_vault_bump = bumps.user_sol_vault;
         let user_key = self.user_account.wallet;
 
         let transfer_ix = anchor_lang::system_program::Transfer {
             from: self.user_sol_vault.to_account_info(),
-            to: self.treasury.to_account_info(),
+            to: provider_sol_payout.to_account_info(),
         };
 
         anchor_lang::system_program::transfer(
@@ -359,22 +711,38 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
         )?;
 
         msg!(
-            "Transferred {} SOL from user vault to treasury",
-            amount as f64 / 1_000_000_000.0
+            "Payment of {} SOL sent to provider {} at {}",
+            amount as f64 / 1_000_000_000.0,
+            self.subscription_service.provider,
+            provider_sol_payout.key()
         );
+
         Ok(())
     }
 
-    /// Transfer USDC to provider account (production implementation)
+    /// Transfer USDC from the protocol treasury to the provider account
     fn transfer_usdc_to_provider(
         &self,
         usdc_amount: u64,
-        _bumps: &ExecuteSubscriptionPaymentBumps,
+        bumps: &ExecuteSubscriptionPaymentBumps,
     ) -> Result<()> {
-        // In simplified version, we transfer SOL directly to provider
-        // This is a placeholder for USDC conversion logic
+        let provider_usdc_account = self
+            .provider_usdc_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidPayoutAccount)?;
+
+        Self::transfer_usdc_from_treasury(
+            &self.token_program,
+            &self.protocol_usdc_treasury,
+            &self.usdc_mint,
+            provider_usdc_account.as_ref(),
+            &self.treasury,
+            bumps.treasury,
+            usdc_amount,
+        )?;
+
         msg!(
-            "Payment of {} USDC equivalent sent to provider {}",
+            "Payment of {} USDC sent to provider {}",
             usdc_amount as f64 / 1_000_000.0, // USDC has 6 decimals
             self.subscription_service.provider
         );
@@ -382,6 +750,154 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
         Ok(())
     }
 
+    /// `transfer_checked` from the treasury-owned USDC account, signed by the treasury PDA
+    pub(crate) fn transfer_usdc_from_treasury(
+        token_program: &Program<'info, Token>,
+        protocol_usdc_treasury: &Account<'info, TokenAccount>,
+        usdc_mint: &Account<'info, Mint>,
+        destination: &AccountInfo<'info>,
+        treasury: &SystemAccount<'info>,
+        treasury_bump: u8,
+        usdc_amount: u64,
+    ) -> Result<()> {
+        if usdc_amount == 0 {
+            return Ok(());
+        }
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: protocol_usdc_treasury.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: destination.clone(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&[b"treasury", &[treasury_bump]]],
+            ),
+            usdc_amount,
+            usdc_mint.decimals,
+        )
+    }
+
+    /// Create a program-derived account of `space` bytes owned by `owner`, like Anchor's
+    /// `init`: an address that was sent lamports beforehand is topped up, allocated and
+    /// assigned instead of making `create_account` fail
+    pub(crate) fn create_pda_account(
+        payer: &AccountInfo<'info>,
+        target: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        space: usize,
+        owner: &Pubkey,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let rent_exempt_lamports = Rent::get()?.minimum_balance(space);
+        let current_lamports = target.lamports();
+        if current_lamports == 0 {
+            return create_account(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    CreateAccount {
+                        from: payer.clone(),
+                        to: target.clone(),
+                    },
+                    signer_seeds,
+                ),
+                rent_exempt_lamports,
+                space as u64,
+                owner,
+            );
+        }
+
+        let shortfall = rent_exempt_lamports.saturating_sub(current_lamports);
+        if shortfall > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: target.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: target.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Assign {
+                    account_to_assign: target.clone(),
+                },
+                signer_seeds,
+            ),
+            owner,
+        )
+    }
+
+    /// Build the `Subscription` record of a charge and its `ProtocolFee` companion.
+    /// Both are numbered with the subscription's payment count before the charge.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn build_payment_records(
+        user_subscription: &Account<'info, UserSubscription>,
+        rent_payer: Pubkey,
+        fee_usd: u64,
+        sol_amount: u64,
+        sol_usd_price: u64,
+        protocol_fee_amount: u64,
+        current_time: i64,
+        (payment_record_bump, protocol_fee_record_bump): (u8, u8),
+    ) -> Result<(PaymentRecord, PaymentRecord)> {
+        // Share of the USD fee that went to the protocol
+        let protocol_fee_usd_cents = if sol_amount == 0 {
+            0
+        } else {
+            u64::try_from(
+                (fee_usd as u128)
+                    .checked_mul(protocol_fee_amount as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    / sol_amount as u128,
+            )
+            .map_err(|_| ErrorCode::ArithmeticOverflow)?
+        };
+
+        let payment_record = PaymentRecord {
+            user: user_subscription.user,
+            provider: user_subscription.provider,
+            user_subscription: user_subscription.key(),
+            subscription_id: user_subscription.subscription_id,
+            service_id: user_subscription.service_id,
+            payment_number: user_subscription.total_payments_made,
+            amount: sol_amount,
+            amount_usd_cents: fee_usd,
+            sol_usd_price,
+            protocol_fee: protocol_fee_amount,
+            payment_date: current_time,
+            payment_type: PaymentType::Subscription,
+            bump: payment_record_bump,
+            rent_payer,
+        };
+
+        let protocol_fee_record = PaymentRecord {
+            amount: protocol_fee_amount,
+            amount_usd_cents: protocol_fee_usd_cents,
+            payment_type: PaymentType::ProtocolFee,
+            bump: protocol_fee_record_bump,
+            ..payment_record.clone()
+        };
+
+        Ok((payment_record, protocol_fee_record))
+    }
+
     /// Handle subscription certificate NFT (simplified version)
     fn handle_subscription_certificate(
         &mut self,
@@ -396,58 +912,135 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
         Ok(())
     }
 
-    /// Update subscription account after successful payment
-    fn update_subscription_after_payment(
-        &mut self,
+    /// Advance a subscription past the cycle just paid and take the charge out of the
+    /// user's deposit and this subscription's lock
+    pub(crate) fn settle_paid_cycle(
+        user_account: &mut User,
+        user_subscription: &mut UserSubscription,
+        quote: &PaymentQuote,
         billing_frequency_days: u64,
         current_time: i64,
     ) -> Result<()> {
-        // Update payment tracking
-        self.user_subscription.last_payment_at = Some(current_time);
-        self.user_subscription.total_payments_made = self
-            .user_subscription
+        user_subscription.last_payment_at = Some(current_time);
+        user_subscription.total_payments_made = user_subscription
             .total_payments_made
             .checked_add(1)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        // Calculate next payment due date
-        let seconds_in_day = 86400_i64;
-        let billing_period_seconds = billing_frequency_days as i64 * seconds_in_day;
+        // The payment buys access until the next due date
+        user_subscription.next_payment_due =
+            Self::next_payment_due_from(current_time, billing_frequency_days)?;
+        user_subscription.access_until = user_subscription.next_payment_due;
 
-        self.user_subscription.next_payment_due = current_time
-            .checked_add(billing_period_seconds)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // A successful charge clears any dunning state and consumes the proration
+        user_subscription.failed_payment_attempts = 0;
+        user_subscription.past_due_since = None;
+        user_subscription.proration_lamports = quote.proration_carry;
+
+        user_account.deposited_sol = user_account
+            .deposited_sol
+            .checked_sub(quote.sol_amount)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        let released = Self::release_locked_sol(user_account, user_subscription, quote.sol_amount)?;
 
         msg!(
-            "Updated subscription: payment #{}, next due at timestamp {}",
-            self.user_subscription.total_payments_made,
-            self.user_subscription.next_payment_due
+            "Updated subscription: payment #{}, next due at timestamp {} | deposited_sol reduced by {} SOL, locked_sol reduced by {} SOL",
+            user_subscription.total_payments_made,
+            user_subscription.next_payment_due,
+            quote.sol_amount as f64 / 1_000_000_000.0,
+            released as f64 / 1_000_000_000.0
         );
 
         Ok(())
     }
 
-    /// Update user account balances after payment
-    fn update_user_balances(&mut self, payment_amount: u64) -> Result<()> {
-        // Deduct from deposited SOL
-        self.user_account.deposited_sol = self
-            .user_account
-            .deposited_sol
-            .checked_sub(payment_amount)
-            .ok_or(ErrorCode::InsufficientBalance)?;
+    /// USD fee of the billing cycle starting at `cycle_start`: a scheduled price applies to
+    /// cycles that start once it is effective, capped by a grandfathered price claimed
+    /// against that change or a later one
+    pub(crate) fn fee_for_cycle(
+        subscription_service: &SubscriptionService,
+        user_subscription: &UserSubscription,
+        cycle_start: i64,
+    ) -> u64 {
+        let (fee_usd, fee_scheduled_at) = match subscription_service.pending_fee_usd {
+            Some(pending_fee_usd) if cycle_start >= subscription_service.price_effective_at => (
+                pending_fee_usd,
+                subscription_service.price_change_scheduled_at,
+            ),
+            _ => (
+                subscription_service.fee_usd,
+                subscription_service.fee_changed_at,
+            ),
+        };
+        Self::grandfathered_fee(user_subscription, fee_usd, fee_scheduled_at)
+    }
 
-        // Update locked SOL for active subscriptions
-        // In production, this would be more sophisticated based on remaining subscription periods
-        msg!(
-            "Updated user balances: deposited_sol reduced by {} SOL",
-            payment_amount as f64 / 1_000_000_000.0
-        );
+    /// `fee_usd`, set by the change scheduled at `fee_scheduled_at`, as paid by this
+    /// subscription: a grandfathered price caps it unless the change was scheduled after
+    /// the claim, in which case the subscriber was given its full notice period
+    pub(crate) fn grandfathered_fee(
+        user_subscription: &UserSubscription,
+        fee_usd: u64,
+        fee_scheduled_at: i64,
+    ) -> u64 {
+        match user_subscription.grandfathered_fee_usd {
+            Some(grandfathered_fee_usd)
+                if user_subscription.grandfathered_change_at >= fee_scheduled_at =>
+            {
+                fee_usd.min(grandfathered_fee_usd)
+            }
+            _ => fee_usd,
+        }
+    }
 
-        Ok(())
+    /// Apply a plan-change adjustment to a period charge, returning the lamports to
+    /// charge now and the credit left over for later periods
+    pub(crate) fn apply_proration(amount: u64, proration_lamports: i64) -> Result<(u64, i64)> {
+        let total = (amount as i128)
+            .checked_add(proration_lamports as i128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if total >= 0 {
+            let charge = u64::try_from(total).map_err(|_| ErrorCode::ArithmeticOverflow)?;
+            Ok((charge, 0))
+        } else {
+            let carry = i64::try_from(total).map_err(|_| ErrorCode::ArithmeticOverflow)?;
+            Ok((0, carry))
+        }
+    }
+
+    /// Start of the next billing period counted from `current_time`
+    pub(crate) fn next_payment_due_from(
+        current_time: i64,
+        billing_frequency_days: u64,
+    ) -> Result<i64> {
+        let seconds_in_day = 86400_i64;
+        let billing_period_seconds = billing_frequency_days as i64 * seconds_in_day;
+
+        Ok(current_time
+            .checked_add(billing_period_seconds)
+            .ok_or(ErrorCode::ArithmeticOverflow)?)
+    }
+
+    /// Release up to `payment_amount` of the subscription's lock from both the
+    /// subscription and `User.locked_sol`, returning the lamports released
+    pub(crate) fn release_locked_sol(
+        user_account: &mut User,
+        user_subscription: &mut UserSubscription,
+        payment_amount: u64,
+    ) -> Result<u64> {
+        let released = payment_amount.min(user_subscription.locked_sol);
+
+        user_subscription.locked_sol -= released;
+        user_account.locked_sol = user_account
+            .locked_sol
+            .checked_sub(released)
+            .ok_or(ErrorCode::LockedSolMismatch)?;
+
+        Ok(released)
     }
 
     /// Get SOL/USD price from Pyth Network - Production Implementation
-    fn get_sol_usd_price_from_pyth(price_feed_account: &AccountInfo) -> Result<u64> {
+    pub(crate) fn get_sol_usd_price_from_pyth(price_feed_account: &AccountInfo) -> Result<u64> {
         let price_feed = SolanaPriceAccount::account_info_to_feed(price_feed_account)
             .map_err(|_| ErrorCode::InvalidPriceFeed)?;
 
@@ -477,7 +1070,7 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
         };
 
         require!(
-            price_cents >= 1000 && price_cents <= 100000,
+            (1000..=100000).contains(&price_cents),
             ErrorCode::InvalidPrice
         );
 
@@ -485,7 +1078,7 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
     }
 
     /// Convert USD cents to SOL lamports
-    fn convert_usd_to_sol_lamports(usd_cents: u64, sol_usd_cents: u64) -> Result<u64> {
+    pub(crate) fn convert_usd_to_sol_lamports(usd_cents: u64, sol_usd_cents: u64) -> Result<u64> {
         let lamports = (usd_cents as u128)
             .checked_mul(1_000_000_000) // LAMPORTS_PER_SOL
             .ok_or(ErrorCode::ArithmeticOverflow)?
@@ -496,7 +1089,7 @@ impl<'info> ExecuteSubscriptionPayment<'info> {
     }
 
     /// Convert SOL lamports to USDC amount (6 decimals)
-    fn convert_sol_to_usdc_amount(sol_lamports: u64, sol_usd_cents: u64) -> Result<u64> {
+    pub(crate) fn convert_sol_to_usdc_amount(sol_lamports: u64, sol_usd_cents: u64) -> Result<u64> {
         let usdc_amount = (sol_lamports as u128)
             .checked_mul(sol_usd_cents as u128)
             .ok_or(ErrorCode::ArithmeticOverflow)?
@@ -542,11 +1135,18 @@ impl<'info> CreatePaymentRecord<'info> {
         self.payment_record.set_inner(PaymentRecord {
             user: self.authority.key(),
             provider: self.authority.key(), // Simplified
-            subscription_id: 0,             // Simplified
+            user_subscription: Pubkey::default(),
+            subscription_id: 0, // Simplified
+            service_id: 0,
+            payment_number: 0,
             amount,
+            amount_usd_cents: 0,
+            sol_usd_price: 0,
+            protocol_fee: 0,
             payment_date: current_time,
             payment_type: PaymentType::Subscription,
             bump: 0, // Will be set by Anchor
+            rent_payer: self.authority.key(),
         });
 
         msg!(
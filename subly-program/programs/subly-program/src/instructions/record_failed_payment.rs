@@ -0,0 +1,238 @@
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    events::*,
+    instructions::{ExecuteSubscriptionPayment, UnsubscribeFromService},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Dunning: the keeper records a due payment the user vault cannot cover.
+/// Attempts follow the configured retry interval, and the subscription is suspended
+/// once it has been past due for longer than the grace period.
+#[derive(Accounts)]
+#[instruction(user: Pubkey, provider: Pubkey, service_id: u64)]
+pub struct RecordFailedPayment<'info> {
+    /// Keeper recording the attempt (the protocol authority unless permissionless mode is on)
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.permissionless_payments
+            || global_state.authority == keeper.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [USER_SEED.as_bytes(), user.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.as_ref(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.is_active @ ErrorCode::SubscriptionNotActive,
+        constraint = !user_subscription.cancel_at_period_end @ ErrorCode::SubscriptionCancelled,
+        constraint = user_subscription.paused_at.is_none() @ ErrorCode::SubscriptionPaused
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.as_ref()],
+        bump = provider_account.bump
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    /// User's SOL vault
+    #[account(
+        seeds = [b"vault", user.as_ref()],
+        bump,
+    )]
+    pub user_sol_vault: SystemAccount<'info>,
+
+    /// Pyth SOL/USD price feed
+    /// CHECK: Pyth price feed account, must match the one in GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
+    pub sol_usd_price_feed: AccountInfo<'info>,
+
+    // Certificate of the subscription, burned on suspension
+    #[account(
+        mut,
+        address = user_subscription.certificate_mint @ ErrorCode::InvalidCertificateMint
+    )]
+    pub certificate_nft_mint: InterfaceAccount<'info, Mint>,
+
+    /// Account holding the certificate, required until it has been burned
+    #[account(
+        mut,
+        token::mint = certificate_nft_mint,
+        token::token_program = token_program
+    )]
+    pub certificate_nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> RecordFailedPayment<'info> {
+    pub fn record_failed_payment(&mut self) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+        // A withdrawn listing is not renewed, so its subscribers are finalised instead of dunned
+        require!(
+            self.subscription_service.is_active,
+            ErrorCode::ServiceNotActive
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time >= self.user_subscription.next_payment_due,
+            ErrorCode::PaymentNotDue
+        );
+
+        // Only payments that really cannot be covered count as failures
+        let sol_usd_price =
+            ExecuteSubscriptionPayment::get_sol_usd_price_from_pyth(&self.sol_usd_price_feed)?;
+        let (amount_due, _) = ExecuteSubscriptionPayment::apply_proration(
+            ExecuteSubscriptionPayment::convert_usd_to_sol_lamports(
+                ExecuteSubscriptionPayment::fee_for_cycle(
+                    &self.subscription_service,
+                    &self.user_subscription,
+                    self.user_subscription.next_payment_due,
+                ),
+                sol_usd_price,
+            )?,
+            self.user_subscription.proration_lamports,
+        )?;
+        // Same check as execute_payment: whatever would stop the charge is a failed attempt
+        require!(
+            ExecuteSubscriptionPayment::check_user_can_pay(
+                self.user_sol_vault.as_ref(),
+                &self.user_account,
+                &self.user_subscription,
+                amount_due,
+            )
+            .is_err(),
+            ErrorCode::PaymentCoverable
+        );
+        let available = self
+            .user_sol_vault
+            .lamports()
+            .min(self.user_account.deposited_sol);
+
+        // Attempt n is allowed from past_due_since + n retry intervals
+        let past_due_since = self
+            .user_subscription
+            .past_due_since
+            .unwrap_or(self.user_subscription.next_payment_due);
+        self.user_subscription.past_due_since = Some(past_due_since);
+        let retry_interval_seconds = (self.global_state.payment_retry_interval_hours as i64)
+            .checked_mul(3600)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let next_attempt_at = retry_interval_seconds
+            .checked_mul(self.user_subscription.failed_payment_attempts as i64)
+            .and_then(|offset| past_due_since.checked_add(offset))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(current_time >= next_attempt_at, ErrorCode::RetryTooEarly);
+
+        self.user_subscription.failed_payment_attempts = self
+            .user_subscription
+            .failed_payment_attempts
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(PaymentFailed {
+            keeper: self.keeper.key(),
+            user_subscription: self.user_subscription.key(),
+            amount_due_lamports: amount_due,
+            available_lamports: available,
+            failed_payment_attempts: self.user_subscription.failed_payment_attempts,
+            past_due_since,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Payment failed for subscription {}: {} SOL due, {} SOL available (attempt #{})",
+            self.user_subscription.key(),
+            amount_due as f64 / 1_000_000_000.0,
+            available as f64 / 1_000_000_000.0,
+            self.user_subscription.failed_payment_attempts
+        );
+
+        // Suspend once the grace period has run out
+        let grace_period_seconds = (self.global_state.grace_period_days as i64)
+            .checked_mul(86400)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let grace_ends_at = past_due_since
+            .checked_add(grace_period_seconds)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        if current_time >= grace_ends_at {
+            self.suspend_subscription(past_due_since, current_time)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deactivate a subscription whose grace period expired, releasing its locked SOL
+    fn suspend_subscription(&mut self, past_due_since: i64, current_time: i64) -> Result<()> {
+        let locked_amount = self.user_subscription.locked_sol;
+        self.user_account.locked_sol = self
+            .user_account
+            .locked_sol
+            .checked_sub(locked_amount)
+            .ok_or(ErrorCode::LockedSolMismatch)?;
+        self.user_subscription.locked_sol = 0;
+
+        UnsubscribeFromService::deactivate_subscription(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            &mut self.subscription_service,
+            &mut self.provider_account,
+            current_time,
+        )?;
+        UnsubscribeFromService::revoke_certificate(
+            &self.token_program,
+            &self.certificate_nft_mint,
+            self.certificate_nft_token_account.as_ref(),
+            &self.user_subscription,
+        )?;
+
+        emit!(SubscriptionSuspended {
+            user_subscription: self.user_subscription.key(),
+            failed_payment_attempts: self.user_subscription.failed_payment_attempts,
+            past_due_since,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Subscription {} suspended after {} failed attempts (past due since {})",
+            self.user_subscription.key(),
+            self.user_subscription.failed_payment_attempts,
+            past_due_since
+        );
+
+        Ok(())
+    }
+}
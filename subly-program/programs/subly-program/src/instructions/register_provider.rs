@@ -1,8 +1,15 @@
 use crate::{constants::*, error::ErrorCode, state::*};
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+    token_interface::{
+        mint_to, set_authority, spl_token_2022::instruction::AuthorityType,
+        spl_token_metadata_interface::state::TokenMetadata, token_metadata_initialize, Mint,
+        MintTo, SetAuthority, Token2022, TokenAccount, TokenMetadataInitialize,
+    },
 };
 
 #[derive(Accounts)]
@@ -21,31 +28,43 @@ pub struct RegisterProvider<'info> {
     #[account(
         init,
         payer = provider,
-        space = Provider::INIT_SPACE,
+        space = 8 + Provider::INIT_SPACE,
         seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
         bump
     )]
     pub provider_account: Account<'info, Provider>,
 
-    // Provider verification NFT
+    /// Service counter left by an earlier deregistration of this wallet, if any
+    /// CHECK: Read only when it holds a `ProviderRecord` owned by this program
+    #[account(
+        seeds = [PROVIDER_RECORD_SEED.as_bytes(), provider.key().as_ref()],
+        bump
+    )]
+    pub provider_record: UncheckedAccount<'info>,
+
+    // Provider NFT: the Provider PDA holds every authority and minting is revoked after one token
     #[account(
         init,
         payer = provider,
         mint::decimals = 0,
-        mint::authority = provider,
-        mint::freeze_authority = provider,
+        mint::authority = provider_account,
+        mint::freeze_authority = provider_account,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = provider_account,
+        extensions::metadata_pointer::metadata_address = provider_nft_mint,
     )]
-    pub provider_nft_mint: Account<'info, Mint>,
+    pub provider_nft_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
         payer = provider,
         associated_token::mint = provider_nft_mint,
         associated_token::authority = provider,
+        associated_token::token_program = token_program,
     )]
-    pub provider_nft_token_account: Account<'info, TokenAccount>,
+    pub provider_nft_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -71,18 +90,92 @@ impl<'info> RegisterProvider<'info> {
         provider_account.description = description;
         provider_account.total_subscribers = 0;
         provider_account.is_verified = false;
+        provider_account.verified_at = None;
+        provider_account.nft_mint = self.provider_nft_mint.key();
         provider_account.created_at = Clock::get()?.unix_timestamp;
+        provider_account.payout_preference = PayoutPreference::Usdc;
+        provider_account.payout_address = self.provider.key();
+        // A returning provider continues after the IDs of the services it left behind
+        provider_account.service_count = if self.provider_record.data_is_empty() {
+            0
+        } else {
+            require_keys_eq!(
+                *self.provider_record.owner,
+                crate::ID,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+            );
+            ProviderRecord::try_deserialize(&mut &self.provider_record.try_borrow_data()?[..])?
+                .service_count
+        };
+        provider_account.live_service_count = 0;
         provider_account.bump = bumps.provider_account;
 
-        // Mint provider verification NFT
-        let cpi_accounts = MintTo {
-            mint: self.provider_nft_mint.to_account_info(),
-            to: self.provider_nft_token_account.to_account_info(),
-            authority: self.provider.to_account_info(),
+        let provider_key = self.provider.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            PROVIDER_SEED.as_bytes(),
+            provider_key.as_ref(),
+            &[bumps.provider_account],
+        ]];
+
+        // Metadata lives in the mint itself and is derived from the Provider account
+        let metadata = TokenMetadata {
+            name: name.clone(),
+            symbol: PROVIDER_NFT_SYMBOL.to_string(),
+            uri: format!(
+                "{}{}",
+                self.global_state.nft_metadata_base_uri,
+                self.provider_account.key()
+            ),
+            ..Default::default()
         };
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        mint_to(cpi_ctx, 1)?;
+        Self::fund_account_growth(
+            &self.provider.to_account_info(),
+            &self.provider_nft_mint.to_account_info(),
+            &self.system_program.to_account_info(),
+            metadata.tlv_size_of()?,
+        )?;
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: self.token_program.to_account_info(),
+                    metadata: self.provider_nft_mint.to_account_info(),
+                    update_authority: self.provider_account.to_account_info(),
+                    mint_authority: self.provider_account.to_account_info(),
+                    mint: self.provider_nft_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )?;
+
+        // Mint the single provider NFT, then close minting for good
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.provider_nft_mint.to_account_info(),
+                    to: self.provider_nft_token_account.to_account_info(),
+                    authority: self.provider_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+        set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.provider_account.to_account_info(),
+                    account_or_mint: self.provider_nft_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
 
         msg!(
             "Provider '{}' registered with NFT: {}",
@@ -92,4 +185,34 @@ impl<'info> RegisterProvider<'info> {
 
         Ok(())
     }
+
+    /// Tops up `account` so it stays rent-exempt after growing by `additional_len` bytes
+    pub(crate) fn fund_account_growth(
+        payer: &AccountInfo<'info>,
+        account: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        additional_len: usize,
+    ) -> Result<()> {
+        let required_lamports = Rent::get()?.minimum_balance(
+            account
+                .data_len()
+                .checked_add(additional_len)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        );
+        let shortfall = required_lamports.saturating_sub(account.lamports());
+        if shortfall > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: account.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        Ok(())
+    }
 }
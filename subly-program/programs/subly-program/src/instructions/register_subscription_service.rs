@@ -17,7 +17,6 @@ pub struct RegisterSubscriptionService<'info> {
     pub provider_account: Account<'info, Provider>,
 
     #[account(
-        mut,
         seeds = [b"global_state"],
         bump = global_state.bump
     )]
@@ -26,11 +25,11 @@ pub struct RegisterSubscriptionService<'info> {
     #[account(
         init,
         payer = provider,
-        space = SubscriptionService::INIT_SPACE,
+        space = 8 + SubscriptionService::INIT_SPACE,
         seeds = [
             SUBSCRIPTION_SERVICE_SEED.as_bytes(),
             provider.key().as_ref(),
-            global_state.total_services.to_le_bytes().as_ref()
+            provider_account.service_count.to_le_bytes().as_ref()
         ],
         bump
     )]
@@ -42,6 +41,7 @@ pub struct RegisterSubscriptionService<'info> {
 }
 
 impl<'info> RegisterSubscriptionService<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn register_subscription_service(
         &mut self,
         name: String,
@@ -49,6 +49,8 @@ impl<'info> RegisterSubscriptionService<'info> {
         fee_usd: u64,
         billing_frequency_days: u64,
         image_url: String,
+        trial_days: u64,
+        charge_first_period: bool,
         bumps: &RegisterSubscriptionServiceBumps,
     ) -> Result<()> {
         require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
@@ -60,34 +62,52 @@ impl<'info> RegisterSubscriptionService<'info> {
         require!(image_url.len() <= MAX_URL_LENGTH, ErrorCode::UrlTooLong);
         require!(fee_usd > 0, ErrorCode::InvalidFeeAmount);
         require!(
-            billing_frequency_days >= MIN_SUBSCRIPTION_PERIOD_DAYS
-                && billing_frequency_days <= MAX_SUBSCRIPTION_PERIOD_DAYS,
+            (MIN_SUBSCRIPTION_PERIOD_DAYS..=MAX_SUBSCRIPTION_PERIOD_DAYS)
+                .contains(&billing_frequency_days),
             ErrorCode::InvalidBillingFrequency
         );
+        require!(trial_days <= MAX_TRIAL_DAYS, ErrorCode::InvalidTrialPeriod);
 
-        let provider_account = &mut self.provider_account;
-        let global_state = &mut self.global_state;
+        let service_id = self.provider_account.service_count;
 
         self.subscription_service.set_inner(SubscriptionService {
             provider: self.provider.key(),
-            service_id: global_state.total_services,
+            service_id,
             name: name.clone(),
             description,
             fee_usd,
+            pending_fee_usd: None,
+            price_effective_at: 0,
+            price_change_scheduled_at: 0,
+            allow_grandfathering: false,
             billing_frequency_days,
+            trial_days,
+            charge_first_period,
             image_url,
             current_subscribers: 0,
             is_active: true,
+            requires_verified_provider: false,
+            pause_policy: PausePolicy::KeepLock,
+            non_transferable_certificates: false,
+            fee_changed_at: 0,
             created_at: Clock::get()?.unix_timestamp,
             bumps: bumps.subscription_service,
         });
 
-        // Update global service count
-        global_state.total_services += 1;
+        // Next service of this provider takes the following ID
+        self.provider_account.service_count = service_id
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.provider_account.live_service_count = self
+            .provider_account
+            .live_service_count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
         msg!(
-            "Subscription service '{}' registered by provider {} with fee ${:.2} per {} days",
+            "Subscription service '{}' (ID {}) registered by provider {} with fee ${:.2} per {} days",
             name,
+            service_id,
             self.provider.key(),
             fee_usd as f64 / 100.0,
             billing_frequency_days
@@ -0,0 +1,170 @@
+use crate::{
+    constants::*, error::ErrorCode, events::*, instructions::ExecuteSubscriptionPayment, state::*,
+};
+use anchor_lang::prelude::*;
+
+/// Provider announces a new fee that applies to billing cycles starting at or after
+/// `effective_at`, which must be at least the protocol's minimum notice away
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct SchedulePriceChange<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+/// Subscriber keeps the current fee across a scheduled price change the provider opened
+/// to grandfathering
+#[derive(Accounts)]
+#[instruction(provider: Pubkey, service_id: u64)]
+pub struct ClaimGrandfatheredPrice<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user.key().as_ref(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = user_subscription.bumps,
+        constraint = user_subscription.user == user.key() @ ErrorCode::UnauthorizedUser,
+        constraint = user_subscription.is_active @ ErrorCode::SubscriptionNotActive
+    )]
+    pub user_subscription: Account<'info, UserSubscription>,
+
+    #[account(
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+impl<'info> SchedulePriceChange<'info> {
+    pub fn schedule_price_change(
+        &mut self,
+        service_id: u64,
+        new_fee_usd: u64,
+        effective_at: i64,
+        allow_grandfathering: bool,
+    ) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+        require!(new_fee_usd > 0, ErrorCode::InvalidFeeAmount);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let min_notice_seconds = (self.global_state.min_price_change_notice_days as i64)
+            .checked_mul(86400)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            effective_at
+                >= current_time
+                    .checked_add(min_notice_seconds)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?,
+            ErrorCode::PriceChangeNoticeTooShort
+        );
+
+        let subscription_service = &mut self.subscription_service;
+
+        // A change already in effect becomes the base price; one still pending is replaced.
+        // Grandfathered prices keep capping the change they were claimed against, while the
+        // new fee only applies from `effective_at`, after the notice period
+        if let Some(pending_fee_usd) = subscription_service.pending_fee_usd {
+            if current_time >= subscription_service.price_effective_at {
+                subscription_service.fee_usd = pending_fee_usd;
+                subscription_service.fee_changed_at =
+                    subscription_service.price_change_scheduled_at;
+            }
+        }
+
+        subscription_service.pending_fee_usd = Some(new_fee_usd);
+        subscription_service.price_effective_at = effective_at;
+        subscription_service.price_change_scheduled_at = current_time;
+        subscription_service.allow_grandfathering = allow_grandfathering;
+
+        emit!(PriceChangeScheduled {
+            provider: self.provider.key(),
+            service_id,
+            current_fee_usd: subscription_service.fee_usd,
+            new_fee_usd,
+            effective_at,
+            allow_grandfathering,
+            timestamp: current_time,
+        });
+
+        msg!(
+            "Service {} fee changes from ${:.2} to ${:.2} for billing cycles starting at {} (grandfathering: {})",
+            service_id,
+            subscription_service.fee_usd as f64 / 100.0,
+            new_fee_usd as f64 / 100.0,
+            effective_at,
+            allow_grandfathering
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> ClaimGrandfatheredPrice<'info> {
+    pub fn claim_grandfathered_price(&mut self) -> Result<()> {
+        let subscription_service = &self.subscription_service;
+
+        // Only subscribers from before the announcement can keep the old price, once per change
+        let already_claimed = self.user_subscription.grandfathered_fee_usd.is_some()
+            && self.user_subscription.grandfathered_change_at
+                == subscription_service.price_change_scheduled_at;
+        require!(
+            subscription_service.pending_fee_usd.is_some()
+                && subscription_service.allow_grandfathering
+                && self.user_subscription.subscribed_at
+                    < subscription_service.price_change_scheduled_at
+                && !already_claimed,
+            ErrorCode::GrandfatheringNotAvailable
+        );
+
+        // A claim against an earlier change is replaced, tying the price to this schedule;
+        // the fee kept is what the subscriber pays now, never more than an earlier claim
+        let fee_usd = ExecuteSubscriptionPayment::grandfathered_fee(
+            &self.user_subscription,
+            subscription_service.fee_usd,
+            subscription_service.fee_changed_at,
+        );
+        self.user_subscription.grandfathered_fee_usd = Some(fee_usd);
+        self.user_subscription.grandfathered_change_at =
+            subscription_service.price_change_scheduled_at;
+
+        emit!(PriceGrandfathered {
+            user_subscription: self.user_subscription.key(),
+            fee_usd,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Subscription {} keeps the ${:.2} price",
+            self.user_subscription.key(),
+            fee_usd as f64 / 100.0
+        );
+
+        Ok(())
+    }
+}
@@ -116,8 +116,7 @@ impl<'info> StakeSol<'info> {
         // Check if user has sufficient available balance
         let available_balance = user_account
             .deposited_sol
-            .checked_sub(user_account.locked_sol)
-            .unwrap_or(0);
+            .saturating_sub(user_account.locked_sol);
 
         require!(
             available_balance >= amount,
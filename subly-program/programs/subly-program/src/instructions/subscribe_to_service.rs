@@ -1,8 +1,18 @@
-use crate::{constants::*, error::ErrorCode, state::*};
+use crate::{constants::*, error::ErrorCode, instructions::ExecuteSubscriptionPayment, state::*};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+    associated_token::{
+        create, get_associated_token_address, get_associated_token_address_with_program_id,
+        AssociatedToken, Create,
+    },
+    token::{Mint, Token, TokenAccount},
+    token_interface::{
+        initialize_mint2, mint_close_authority_initialize, mint_to,
+        non_transferable_mint_initialize, permanent_delegate_initialize, set_authority,
+        spl_token_2022::{self, extension::ExtensionType, instruction::AuthorityType},
+        InitializeMint2, MintCloseAuthorityInitialize, MintTo, NonTransferableMintInitialize,
+        PermanentDelegateInitialize, SetAuthority, Token2022,
+    },
 };
 use pyth_sdk_solana::state::SolanaPriceAccount;
 
@@ -40,10 +50,11 @@ pub struct SubscribeToService<'info> {
     )]
     pub provider_account: Account<'info, Provider>,
 
+    /// Created on first subscribe, reactivated in place after an unsubscribe
     #[account(
-        init,
+        init_if_needed,
         payer = user,
-        space = UserSubscription::INIT_SPACE,
+        space = 8 + UserSubscription::INIT_SPACE,
         seeds = [
             USER_SUBSCRIPTION_SEED.as_bytes(),
             user.key().as_ref(),
@@ -54,6 +65,21 @@ pub struct SubscribeToService<'info> {
     )]
     pub user_subscription: Account<'info, UserSubscription>,
 
+    /// Created on first subscribe and never closed: one free trial per wallet per service,
+    /// and a certificate counter that survives closing the subscription
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TrialRecord::INIT_SPACE,
+        seeds = [
+            TRIAL_RECORD_SEED.as_bytes(),
+            user.key().as_ref(),
+            subscription_service.key().as_ref()
+        ],
+        bump
+    )]
+    pub trial_record: Account<'info, TrialRecord>,
+
     #[account(
         mut,
         seeds = [b"global_state"],
@@ -65,25 +91,95 @@ pub struct SubscribeToService<'info> {
     /// CHECK: Pyth price feed account
     pub sol_usd_price_feed: AccountInfo<'info>,
 
-    // Subscription certificate NFT
+    /// Subscription certificate NFT, a Token-2022 mint created in the instruction because the
+    /// NonTransferable extension depends on the service. The subscription PDA holds its
+    /// authorities and minting is revoked after one token.
+    /// CHECK: Created and initialised by this instruction at the derived address
+    #[account(
+        mut,
+        seeds = [
+            CERTIFICATE_MINT_SEED.as_bytes(),
+            user_subscription.key().as_ref(),
+            trial_record.certificates_issued.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub certificate_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The user's certificate ATA, created by the associated token program
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &user.key(),
+            &certificate_nft_mint.key(),
+            &certificate_token_program.key()
+        ) @ ErrorCode::InvalidCertificateAccount
+    )]
+    pub certificate_nft_token_account: UncheckedAccount<'info>,
+
+    // First-period payment accounts, required when the service charges at subscribe time
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub user_sol_vault: Option<SystemAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+
+    /// Protocol's USDC treasury token account, the treasury's USDC ATA
+    #[account(mut)]
+    pub protocol_usdc_treasury: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(address = global_state.usdc_mint @ ErrorCode::InvalidUsdcMint)]
+    pub usdc_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Provider's USDC ATA when the provider is paid in USDC
+    #[account(mut)]
+    pub provider_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Provider's SOL payout address when the provider is paid in SOL
+    #[account(
+        mut,
+        address = provider_account.payout_address @ ErrorCode::InvalidPayoutAccount
+    )]
+    pub provider_sol_payout: Option<SystemAccount<'info>>,
+
+    /// Audit record of the first charge; keyed by the fresh certificate mint because
+    /// `subscribed_at` is only known once the transaction executes
     #[account(
         init,
         payer = user,
-        mint::decimals = 0,
-        mint::authority = user,
-        mint::freeze_authority = user,
+        space = 8 + PaymentRecord::INIT_SPACE,
+        seeds = [
+            PAYMENT_RECORD_SEED.as_bytes(),
+            user_subscription.key().as_ref(),
+            certificate_nft_mint.key().as_ref()
+        ],
+        bump
     )]
-    pub certificate_nft_mint: Account<'info, Mint>,
+    pub payment_record: Option<Box<Account<'info, PaymentRecord>>>,
 
     #[account(
         init,
         payer = user,
-        associated_token::mint = certificate_nft_mint,
-        associated_token::authority = user,
+        space = 8 + PaymentRecord::INIT_SPACE,
+        seeds = [
+            PROTOCOL_FEE_RECORD_SEED.as_bytes(),
+            user_subscription.key().as_ref(),
+            certificate_nft_mint.key().as_ref()
+        ],
+        bump
     )]
-    pub certificate_nft_token_account: Account<'info, TokenAccount>,
+    pub protocol_fee_record: Option<Box<Account<'info, PaymentRecord>>>,
 
     pub token_program: Program<'info, Token>,
+    pub certificate_token_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -103,6 +199,21 @@ impl<'info> SubscribeToService<'info> {
             ErrorCode::InvalidPriceFeed
         );
 
+        // An existing subscription account can only be reused once it has been cancelled
+        let is_reactivation = self.user_subscription.user != Pubkey::default();
+        if is_reactivation {
+            require!(
+                !self.user_subscription.is_active,
+                ErrorCode::SubscriptionAlreadyExists
+            );
+            require!(
+                self.user_subscription.locked_sol == 0,
+                ErrorCode::LockedSolMismatch
+            );
+        }
+        // Lifetime payment count survives reactivation: payment records are indexed by it
+        let total_payments_made = self.user_subscription.total_payments_made;
+
         let subscription_service = &mut self.subscription_service;
         let user_account = &mut self.user_account;
         let provider_account = &mut self.provider_account;
@@ -113,22 +224,43 @@ impl<'info> SubscribeToService<'info> {
         // Calculate required locked amount (12 months of subscription fees) using real price
         let monthly_fee_lamports =
             Self::convert_usd_to_sol_lamports(subscription_service.fee_usd, sol_usd_price_cents)?;
-        let required_locked_amount = monthly_fee_lamports * 12; // Lock 12 months worth
+        let required_locked_amount = monthly_fee_lamports
+            .checked_mul(SUBSCRIPTION_LOCK_PERIODS)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
         // Check if user has sufficient available balance
         let available_balance = user_account
             .deposited_sol
-            .checked_sub(user_account.locked_sol)
-            .unwrap_or(0);
+            .saturating_sub(user_account.locked_sol);
 
         require!(
             available_balance >= required_locked_amount,
             ErrorCode::InsufficientAvailableBalance
         );
 
+        // The first charge is due right away unless the wallet still has a trial for this service
         let current_time = Clock::get()?.unix_timestamp;
-        let next_payment_due =
-            current_time + (subscription_service.billing_frequency_days as i64 * 86400);
+        let trial_ends_at = if subscription_service.trial_days > 0
+            && self.trial_record.trial_started_at.is_none()
+        {
+            Some(current_time + (subscription_service.trial_days as i64 * 86400))
+        } else {
+            None
+        };
+        let next_payment_due = trial_ends_at.unwrap_or(current_time);
+        let certificate_index = self.trial_record.certificates_issued;
+        self.trial_record.set_inner(TrialRecord {
+            user: self.user.key(),
+            subscription_service: subscription_service.key(),
+            trial_started_at: self
+                .trial_record
+                .trial_started_at
+                .or(trial_ends_at.map(|_| current_time)),
+            certificates_issued: certificate_index
+                .checked_add(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+            bump: bumps.trial_record,
+        });
 
         // Create subscription
         self.user_subscription.set_inner(UserSubscription {
@@ -139,9 +271,21 @@ impl<'info> SubscribeToService<'info> {
             subscribed_at: current_time,
             last_payment_at: None,
             next_payment_due,
-            total_payments_made: 0,
+            trial_ends_at,
+            total_payments_made,
+            locked_sol: required_locked_amount,
             is_active: true,
+            cancel_at_period_end: false,
+            access_until: next_payment_due,
+            failed_payment_attempts: 0,
+            past_due_since: None,
+            paused_at: None,
+            lock_released_on_pause: 0,
+            grandfathered_fee_usd: None,
+            grandfathered_change_at: 0,
+            proration_lamports: 0,
             unsubscribed_at: None,
+            certificate_mint: self.certificate_nft_mint.key(),
             bumps: bumps.user_subscription,
         });
 
@@ -150,16 +294,10 @@ impl<'info> SubscribeToService<'info> {
             .locked_sol
             .checked_add(required_locked_amount)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
-
-        // Mint subscription certificate NFT
-        let cpi_accounts = MintTo {
-            mint: self.certificate_nft_mint.to_account_info(),
-            to: self.certificate_nft_token_account.to_account_info(),
-            authority: self.user.to_account_info(),
-        };
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        mint_to(cpi_ctx, 1)?;
+        user_account.active_subscriptions = user_account
+            .active_subscriptions
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
         // Update counters
         subscription_service.current_subscribers += 1;
@@ -174,14 +312,399 @@ impl<'info> SubscribeToService<'info> {
             subscription_service.billing_frequency_days
         );
 
+        if let Some(trial_ends_at) = trial_ends_at {
+            msg!(
+                "Free trial of {} days, first payment due at timestamp {}",
+                subscription_service.trial_days,
+                trial_ends_at
+            );
+        }
+
+        if is_reactivation {
+            msg!(
+                "Reactivated subscription {} after {} previous payments",
+                self.user_subscription.key(),
+                total_payments_made
+            );
+        }
+
+        // Mint subscription certificate NFT
+        Self::mint_certificate(
+            &self.user,
+            &self.user_subscription,
+            &self.certificate_nft_mint.to_account_info(),
+            &self.certificate_nft_token_account.to_account_info(),
+            &self.certificate_token_program,
+            &self.associated_token_program,
+            &self.system_program,
+            self.subscription_service.non_transferable_certificates,
+            (certificate_index, bumps.certificate_nft_mint),
+        )?;
         msg!(
             "Subscription certificate NFT minted: {}",
             self.certificate_nft_mint.key()
         );
 
+        // Services billed in advance collect the first period right away
+        if self.subscription_service.charge_first_period && trial_ends_at.is_none() {
+            self.collect_first_payment(current_time, bumps)?;
+        } else {
+            require!(
+                self.payment_record.is_none() && self.protocol_fee_record.is_none(),
+                ErrorCode::InvalidFirstPaymentAccounts
+            );
+        }
+
         Ok(())
     }
 
+    /// Create the certificate mint and the user's token account, mint the single certificate
+    /// and revoke further minting; the subscription PDA signs as mint authority.
+    /// `user_subscription` must already hold its user, provider, service ID and bump.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn mint_certificate(
+        user: &Signer<'info>,
+        user_subscription: &Account<'info, UserSubscription>,
+        certificate_nft_mint: &AccountInfo<'info>,
+        certificate_nft_token_account: &AccountInfo<'info>,
+        certificate_token_program: &Program<'info, Token2022>,
+        associated_token_program: &Program<'info, AssociatedToken>,
+        system_program: &Program<'info, System>,
+        non_transferable: bool,
+        (certificate_index, certificate_mint_bump): (u64, u8),
+    ) -> Result<()> {
+        let user_subscription_key = user_subscription.key();
+        let certificate_index_bytes = certificate_index.to_le_bytes();
+        let service_id_bytes = user_subscription.service_id.to_le_bytes();
+        let mint_seeds: &[&[&[u8]]] = &[&[
+            CERTIFICATE_MINT_SEED.as_bytes(),
+            user_subscription_key.as_ref(),
+            certificate_index_bytes.as_ref(),
+            &[certificate_mint_bump],
+        ]];
+        let subscription_seeds: &[&[&[u8]]] = &[&[
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user_subscription.user.as_ref(),
+            user_subscription.provider.as_ref(),
+            service_id_bytes.as_ref(),
+            &[user_subscription.bumps],
+        ]];
+
+        // The subscription PDA burns the certificate when the subscription ends
+        // and can then close the mint
+        let extensions: &[ExtensionType] = if non_transferable {
+            &[
+                ExtensionType::MintCloseAuthority,
+                ExtensionType::PermanentDelegate,
+                ExtensionType::NonTransferable,
+            ]
+        } else {
+            &[
+                ExtensionType::MintCloseAuthority,
+                ExtensionType::PermanentDelegate,
+            ]
+        };
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)?;
+
+        // The mint address is predictable, so lamports sent to it first must not block it
+        ExecuteSubscriptionPayment::create_pda_account(
+            &user.to_account_info(),
+            certificate_nft_mint,
+            &system_program.to_account_info(),
+            mint_len,
+            &certificate_token_program.key(),
+            mint_seeds,
+        )?;
+
+        // Extensions are initialised before the mint itself
+        mint_close_authority_initialize(
+            CpiContext::new(
+                certificate_token_program.to_account_info(),
+                MintCloseAuthorityInitialize {
+                    token_program_id: certificate_token_program.to_account_info(),
+                    mint: certificate_nft_mint.clone(),
+                },
+            ),
+            Some(&user_subscription_key),
+        )?;
+        permanent_delegate_initialize(
+            CpiContext::new(
+                certificate_token_program.to_account_info(),
+                PermanentDelegateInitialize {
+                    token_program_id: certificate_token_program.to_account_info(),
+                    mint: certificate_nft_mint.clone(),
+                },
+            ),
+            &user_subscription_key,
+        )?;
+        if non_transferable {
+            non_transferable_mint_initialize(CpiContext::new(
+                certificate_token_program.to_account_info(),
+                NonTransferableMintInitialize {
+                    token_program_id: certificate_token_program.to_account_info(),
+                    mint: certificate_nft_mint.clone(),
+                },
+            ))?;
+        }
+        initialize_mint2(
+            CpiContext::new(
+                certificate_token_program.to_account_info(),
+                InitializeMint2 {
+                    mint: certificate_nft_mint.clone(),
+                },
+            ),
+            0,
+            &user_subscription_key,
+            Some(&user_subscription_key),
+        )?;
+
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: user.to_account_info(),
+                associated_token: certificate_nft_token_account.clone(),
+                authority: user.to_account_info(),
+                mint: certificate_nft_mint.clone(),
+                system_program: system_program.to_account_info(),
+                token_program: certificate_token_program.to_account_info(),
+            },
+        ))?;
+
+        mint_to(
+            CpiContext::new_with_signer(
+                certificate_token_program.to_account_info(),
+                MintTo {
+                    mint: certificate_nft_mint.clone(),
+                    to: certificate_nft_token_account.clone(),
+                    authority: user_subscription.to_account_info(),
+                },
+                subscription_seeds,
+            ),
+            1,
+        )?;
+        set_authority(
+            CpiContext::new_with_signer(
+                certificate_token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: user_subscription.to_account_info(),
+                    account_or_mint: certificate_nft_mint.clone(),
+                },
+                subscription_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Charge the first billing period with the same fee split and audit trail as `execute_payment`
+    fn collect_first_payment(
+        &mut self,
+        current_time: i64,
+        bumps: &SubscribeToServiceBumps,
+    ) -> Result<()> {
+        let (
+            Some(user_sol_vault),
+            Some(treasury),
+            Some(protocol_usdc_treasury),
+            Some(usdc_mint),
+            Some(payment_record_account),
+            Some(protocol_fee_record_account),
+            Some(vault_bump),
+            Some(treasury_bump),
+            Some(payment_record_bump),
+            Some(protocol_fee_record_bump),
+        ) = (
+            self.user_sol_vault.as_ref(),
+            self.treasury.as_ref(),
+            self.protocol_usdc_treasury.as_ref(),
+            self.usdc_mint.as_ref(),
+            self.payment_record.as_mut(),
+            self.protocol_fee_record.as_mut(),
+            bumps.user_sol_vault,
+            bumps.treasury,
+            bumps.payment_record,
+            bumps.protocol_fee_record,
+        )
+        else {
+            return err!(ErrorCode::InvalidFirstPaymentAccounts);
+        };
+        require_keys_eq!(
+            protocol_usdc_treasury.key(),
+            get_associated_token_address(&treasury.key(), &usdc_mint.key()),
+            ErrorCode::InvalidFirstPaymentAccounts
+        );
+
+        let sol_usd_price =
+            ExecuteSubscriptionPayment::get_sol_usd_price_from_pyth(&self.sol_usd_price_feed)?;
+        let fee_usd = ExecuteSubscriptionPayment::fee_for_cycle(
+            &self.subscription_service,
+            &self.user_subscription,
+            current_time,
+        );
+        let sol_amount_needed =
+            ExecuteSubscriptionPayment::convert_usd_to_sol_lamports(fee_usd, sol_usd_price)?;
+        ExecuteSubscriptionPayment::check_user_can_pay(
+            user_sol_vault.as_ref(),
+            &self.user_account,
+            &self.user_subscription,
+            sol_amount_needed,
+        )?;
+
+        // No keeper is involved, so the whole protocol fee stays with the treasury
+        let (protocol_fee_amount, provider_payment_amount) =
+            ExecuteSubscriptionPayment::split_protocol_fee(
+                sol_amount_needed,
+                self.global_state.protocol_fee_bps,
+            )?;
+        let payout_preference = self.provider_account.payout_preference;
+        let (provider_sol_amount, usdc_amount_for_provider) = match payout_preference {
+            PayoutPreference::Usdc => {
+                let provider_usdc_account = self
+                    .provider_usdc_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidPayoutAccount)?;
+                require_keys_eq!(
+                    provider_usdc_account.key(),
+                    get_associated_token_address(&self.provider_account.wallet, &usdc_mint.key()),
+                    ErrorCode::InvalidPayoutAccount
+                );
+                let usdc_amount = ExecuteSubscriptionPayment::convert_sol_to_usdc_amount(
+                    provider_payment_amount,
+                    sol_usd_price,
+                )?;
+                require!(
+                    protocol_usdc_treasury.amount >= usdc_amount,
+                    ErrorCode::InsufficientTreasuryLiquidity
+                );
+                (0, usdc_amount)
+            }
+            PayoutPreference::Sol => {
+                require!(
+                    self.provider_sol_payout.is_some(),
+                    ErrorCode::InvalidPayoutAccount
+                );
+                (provider_payment_amount, 0)
+            }
+        };
+        let treasury_amount = sol_amount_needed
+            .checked_sub(provider_sol_amount)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        // Audit trail numbered like keeper-collected payments
+        let (payment_record, protocol_fee_record) =
+            ExecuteSubscriptionPayment::build_payment_records(
+                &self.user_subscription,
+                self.user.key(),
+                fee_usd,
+                sol_amount_needed,
+                sol_usd_price,
+                protocol_fee_amount,
+                current_time,
+                (payment_record_bump, protocol_fee_record_bump),
+            )?;
+        payment_record_account.set_inner(payment_record);
+        protocol_fee_record_account.set_inner(protocol_fee_record);
+
+        // Move the funds
+        let user_key = self.user.key();
+        let vault_seeds: &[&[&[u8]]] = &[&[b"vault", user_key.as_ref(), &[vault_bump]]];
+        self.transfer_from_vault(
+            user_sol_vault,
+            treasury.as_ref(),
+            treasury_amount,
+            vault_seeds,
+        )?;
+        match payout_preference {
+            PayoutPreference::Usdc => {
+                let provider_usdc_account = self
+                    .provider_usdc_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidPayoutAccount)?;
+                ExecuteSubscriptionPayment::transfer_usdc_from_treasury(
+                    &self.token_program,
+                    protocol_usdc_treasury,
+                    usdc_mint,
+                    &provider_usdc_account.to_account_info(),
+                    treasury,
+                    treasury_bump,
+                    usdc_amount_for_provider,
+                )?;
+            }
+            PayoutPreference::Sol => {
+                let provider_sol_payout = self
+                    .provider_sol_payout
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidPayoutAccount)?;
+                self.transfer_from_vault(
+                    user_sol_vault,
+                    provider_sol_payout.as_ref(),
+                    provider_sol_amount,
+                    vault_seeds,
+                )?;
+            }
+        }
+
+        // The payment buys the first period
+        let next_payment_due = ExecuteSubscriptionPayment::next_payment_due_from(
+            current_time,
+            self.subscription_service.billing_frequency_days,
+        )?;
+        self.user_subscription.last_payment_at = Some(current_time);
+        self.user_subscription.total_payments_made = self
+            .user_subscription
+            .total_payments_made
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.user_subscription.next_payment_due = next_payment_due;
+        self.user_subscription.access_until = next_payment_due;
+        self.user_account.deposited_sol = self
+            .user_account
+            .deposited_sol
+            .checked_sub(sol_amount_needed)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        ExecuteSubscriptionPayment::release_locked_sol(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            sol_amount_needed,
+        )?;
+
+        msg!(
+            "FIRST PAYMENT COLLECTED: User {} paid {} SOL (${:.2}) | Protocol fee: {} SOL | Provider paid: {} SOL / {} USDC | Next due: {}",
+            user_key,
+            sol_amount_needed as f64 / 1_000_000_000.0,
+            fee_usd as f64 / 100.0,
+            protocol_fee_amount as f64 / 1_000_000_000.0,
+            provider_sol_amount as f64 / 1_000_000_000.0,
+            usdc_amount_for_provider as f64 / 1_000_000.0,
+            next_payment_due
+        );
+
+        Ok(())
+    }
+
+    fn transfer_from_vault(
+        &self,
+        vault: &SystemAccount<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+        vault_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: vault.to_account_info(),
+                    to: to.clone(),
+                },
+                vault_seeds,
+            ),
+            amount,
+        )
+    }
+
     /// Get SOL/USD price from Pyth Network - REAL IMPLEMENTATION
     fn get_sol_usd_price_from_pyth(price_feed_account: &AccountInfo) -> Result<u64> {
         // Load price feed from Pyth account using the correct API
@@ -228,7 +751,7 @@ impl<'info> SubscribeToService<'info> {
 
         // Validate reasonable price range ($10 - $1000 per SOL)
         require!(
-            price_cents >= 1000 && price_cents <= 100000,
+            (1000..=100000).contains(&price_cents),
             ErrorCode::InvalidPrice
         );
 
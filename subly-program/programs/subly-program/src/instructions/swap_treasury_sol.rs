@@ -0,0 +1,173 @@
+use crate::{
+    error::ErrorCode,
+    events::*,
+    instructions::ExecuteSubscriptionPayment,
+    state::*,
+    swap_adapter::{self, SwapSolToTokenAccounts},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Rebalance the treasury: swap collected SOL into USDC through the configured swap adapter
+#[derive(Accounts)]
+pub struct SwapTreasurySol<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Treasury holding the SOL collected from subscription payments
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Protocol's USDC treasury token account receiving the swap output
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = treasury
+    )]
+    pub protocol_usdc_treasury: Account<'info, TokenAccount>,
+
+    #[account(address = global_state.usdc_mint @ ErrorCode::InvalidUsdcMint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    /// Pyth SOL/USD price feed used to derive the minimum output
+    /// CHECK: Pyth price feed account, must match the one in GlobalState
+    #[account(address = global_state.sol_usd_price_feed @ ErrorCode::InvalidPriceFeed)]
+    pub sol_usd_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Swap adapter program configured in GlobalState
+    #[account(
+        executable,
+        address = global_state.swap_program @ ErrorCode::InvalidSwapProgram
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    /// CHECK: Pool state, validated by the swap program
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: Pool SOL reserve, validated by the swap program
+    #[account(mut)]
+    pub pool_sol_reserve: UncheckedAccount<'info>,
+
+    /// CHECK: Pool USDC reserve, validated by the swap program
+    #[account(mut)]
+    pub pool_token_reserve: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapTreasurySol<'info> {
+    pub fn swap_treasury_sol(
+        &mut self,
+        sol_amount: u64,
+        bumps: &SwapTreasurySolBumps,
+    ) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+        require!(sol_amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            self.global_state.swap_program != Pubkey::default(),
+            ErrorCode::SwapNotConfigured
+        );
+
+        // Keep the treasury rent-exempt after the swap
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        require!(
+            self.treasury.lamports().saturating_sub(rent_exempt_minimum) >= sol_amount,
+            ErrorCode::InsufficientBalance
+        );
+
+        // Minimum output: Pyth fair value minus the configured slippage tolerance
+        let sol_usd_price =
+            ExecuteSubscriptionPayment::get_sol_usd_price_from_pyth(&self.sol_usd_price_feed)?;
+        let expected_usdc =
+            ExecuteSubscriptionPayment::convert_sol_to_usdc_amount(sol_amount, sol_usd_price)?;
+        let minimum_usdc_out =
+            Self::apply_slippage(expected_usdc, self.global_state.max_swap_slippage_bps)?;
+
+        let usdc_before = self.protocol_usdc_treasury.amount;
+        let treasury_lamports_before = self.treasury.lamports();
+
+        swap_adapter::swap_sol_to_token(
+            &self.swap_program,
+            SwapSolToTokenAccounts {
+                pool: &self.pool,
+                pool_sol_reserve: &self.pool_sol_reserve,
+                pool_token_reserve: &self.pool_token_reserve,
+                trader: self.treasury.as_ref(),
+                trader_token_account: self.protocol_usdc_treasury.as_ref(),
+                token_program: self.token_program.as_ref(),
+                system_program: self.system_program.as_ref(),
+            },
+            sol_amount,
+            minimum_usdc_out,
+            &[&[b"treasury", &[bumps.treasury]]],
+        )?;
+
+        // Do not trust the adapter: the treasury signed the CPI, so verify what actually left
+        let sol_spent = treasury_lamports_before.saturating_sub(self.treasury.lamports());
+        require!(sol_spent <= sol_amount, ErrorCode::SwapInputExceeded);
+        require!(
+            self.treasury.lamports() >= rent_exempt_minimum,
+            ErrorCode::InsufficientBalance
+        );
+
+        // and what actually arrived
+        self.protocol_usdc_treasury.reload()?;
+        let usdc_received = self
+            .protocol_usdc_treasury
+            .amount
+            .checked_sub(usdc_before)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        require!(
+            usdc_received >= minimum_usdc_out,
+            ErrorCode::SlippageExceeded
+        );
+
+        emit!(TreasurySwapped {
+            authority: self.authority.key(),
+            swap_program: self.swap_program.key(),
+            sol_amount,
+            usdc_received,
+            minimum_usdc_out,
+            sol_usd_price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Treasury swapped {} SOL for {} USDC (min {} USDC at ${:.2}/SOL)",
+            sol_amount as f64 / 1_000_000_000.0,
+            usdc_received as f64 / 1_000_000.0,
+            minimum_usdc_out as f64 / 1_000_000.0,
+            sol_usd_price as f64 / 100.0
+        );
+
+        Ok(())
+    }
+
+    fn apply_slippage(expected_amount: u64, slippage_bps: u16) -> Result<u64> {
+        let minimum = (expected_amount as u128)
+            .checked_mul(10000 - slippage_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(u64::try_from(minimum).map_err(|_| ErrorCode::ArithmeticOverflow)?)
+    }
+}
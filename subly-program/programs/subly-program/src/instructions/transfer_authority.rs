@@ -0,0 +1,72 @@
+use crate::{error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Step 1 of the authority transfer: the current authority nominates a successor
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Step 2 of the authority transfer: the nominee proves control of the new key
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.pending_authority.is_some() @ ErrorCode::NoPendingAuthority,
+        constraint = global_state.pending_authority == Some(new_authority.key()) @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+impl<'info> ProposeAuthority<'info> {
+    /// Passing `None` cancels a pending proposal
+    pub fn propose_authority(&mut self, new_authority: Option<Pubkey>) -> Result<()> {
+        self.global_state.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            authority: self.authority.key(),
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Pending protocol authority set to {:?}", new_authority);
+
+        Ok(())
+    }
+}
+
+impl<'info> AcceptAuthority<'info> {
+    pub fn accept_authority(&mut self) -> Result<()> {
+        let old_authority = self.global_state.authority;
+        let new_authority = self.new_authority.key();
+
+        self.global_state.authority = new_authority;
+        self.global_state.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Protocol authority transferred: {} -> {}",
+            old_authority,
+            new_authority
+        );
+
+        Ok(())
+    }
+}
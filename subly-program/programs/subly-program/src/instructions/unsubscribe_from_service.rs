@@ -1,10 +1,10 @@
 use crate::{constants::*, error::ErrorCode, state::*};
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{burn, Burn, Mint, Token, TokenAccount},
+use anchor_spl::token_interface::{
+    burn, freeze_account, get_mint_extension_data,
+    spl_token_2022::extension::permanent_delegate::PermanentDelegate, thaw_account, Burn,
+    FreezeAccount, Mint, ThawAccount, Token2022, TokenAccount,
 };
-use pyth_sdk_solana::state::SolanaPriceAccount;
 
 #[derive(Accounts)]
 #[instruction(provider: Pubkey, service_id: u64)]
@@ -60,24 +60,23 @@ pub struct UnsubscribeFromService<'info> {
     )]
     pub global_state: Account<'info, GlobalState>,
 
-    // Pyth price feed for SOL/USD conversion
-    /// CHECK: This account is validated in the instruction method to match the price feed in GlobalState
-    pub sol_usd_price_feed: AccountInfo<'info>,
-
-    // Subscription certificate NFT to burn
-    #[account(mut)]
-    pub certificate_nft_mint: Account<'info, Mint>,
+    // Subscription certificate NFT to burn, the one minted for this subscription
+    #[account(
+        mut,
+        address = user_subscription.certificate_mint @ ErrorCode::InvalidCertificateMint
+    )]
+    pub certificate_nft_mint: InterfaceAccount<'info, Mint>,
 
+    /// Whichever account holds the certificate now, required until it has been burned;
+    /// the subscription PDA burns it as the mint's permanent delegate
     #[account(
         mut,
-        associated_token::mint = certificate_nft_mint,
-        associated_token::authority = user,
-        constraint = certificate_nft_token_account.amount > 0 @ ErrorCode::NoCertificateToDestroy
+        token::mint = certificate_nft_mint,
+        token::token_program = token_program
     )]
-    pub certificate_nft_token_account: Account<'info, TokenAccount>,
+    pub certificate_nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
@@ -85,193 +84,202 @@ impl<'info> UnsubscribeFromService<'info> {
     pub fn unsubscribe_from_service(&mut self, _provider: Pubkey, _service_id: u64) -> Result<()> {
         require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
 
-        // Verify the Pyth price feed account matches the one in GlobalState
-        require!(
-            self.sol_usd_price_feed.key() == self.global_state.sol_usd_price_feed,
-            ErrorCode::InvalidPriceFeed
-        );
-
         let user_subscription = &mut self.user_subscription;
         let user_account = &mut self.user_account;
-        let subscription_service = &mut self.subscription_service;
-        let provider_account = &mut self.provider_account;
 
-        // Validate that the subscription is active
+        // Validate that the subscription is active and not already being cancelled
         require!(
             user_subscription.is_active,
             ErrorCode::SubscriptionNotActive
         );
+        require!(
+            !user_subscription.cancel_at_period_end,
+            ErrorCode::SubscriptionCancelled
+        );
+        // A plan change is settled by the next payment; cancelling before it would skip
+        // an upgrade charge or drop a downgrade credit
+        require!(
+            user_subscription.proration_lamports == 0,
+            ErrorCode::ProrationUnsettled
+        );
+        // A paused subscription has its billing clock stopped and, under ReleaseLock, nothing
+        // locked any more; it ends immediately instead of running out an access period
+        let was_paused = user_subscription.paused_at.is_some();
+        user_subscription.paused_at = None;
+        user_subscription.lock_released_on_pause = 0;
 
-        // Calculate the amount to unlock (remaining subscription fees)
         let current_time = Clock::get()?.unix_timestamp;
-        let subscription_days = subscription_service.billing_frequency_days;
         let seconds_per_day = 86400i64;
-        let billing_period_seconds = subscription_days as i64 * seconds_per_day;
-
-        // Calculate how much time is left in current billing period
-        let time_since_subscription = current_time - user_subscription.subscribed_at;
-        let _full_periods_passed = time_since_subscription / billing_period_seconds;
-        let time_in_current_period = time_since_subscription % billing_period_seconds;
-        let _remaining_time_in_period = billing_period_seconds - time_in_current_period;
-
-        // Get real SOL/USD price from Pyth
-        let sol_usd_price_cents = Self::get_sol_usd_price_from_pyth(&self.sol_usd_price_feed)?;
 
-        // Calculate monthly fee in lamports using real Pyth price
-        let monthly_fee_lamports =
-            Self::convert_usd_to_sol_lamports(subscription_service.fee_usd, sol_usd_price_cents)?;
-
-        // Calculate how much SOL to unlock
-        // Unlock all remaining locked funds for this subscription (since user is canceling)
-        let locked_amount_for_subscription = monthly_fee_lamports
-            .checked_mul(12)
-            .ok_or(ErrorCode::ArithmeticOverflow)?; // We locked 12 months initially
-
-        // Free up locked SOL
+        // Release exactly what this subscription still holds, whatever the SOL price is now
+        let locked_amount_for_subscription = user_subscription.locked_sol;
         user_account.locked_sol = user_account
             .locked_sol
             .checked_sub(locked_amount_for_subscription)
-            .unwrap_or(0);
-
-        // Burn the subscription certificate NFT
-        let cpi_accounts = Burn {
-            mint: self.certificate_nft_mint.to_account_info(),
-            from: self.certificate_nft_token_account.to_account_info(),
-            authority: self.user.to_account_info(),
-        };
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        burn(cpi_ctx, 1)?;
-
-        // Deactivate subscription
-        user_subscription.is_active = false;
-        user_subscription.unsubscribed_at = Some(current_time);
-
-        // Update counters
-        subscription_service.current_subscribers =
-            subscription_service.current_subscribers.saturating_sub(1);
-        provider_account.total_subscribers = provider_account.total_subscribers.saturating_sub(1);
+            .ok_or(ErrorCode::LockedSolMismatch)?;
+        user_subscription.locked_sol = 0;
 
-        msg!(
-            "User {} successfully unsubscribed from service '{}' (Provider: {})",
-            self.user.key(),
-            subscription_service.name,
-            user_subscription.provider
-        );
+        // Burn the subscription certificate NFT wherever it has been moved to
+        Self::revoke_certificate(
+            &self.token_program,
+            &self.certificate_nft_mint,
+            self.certificate_nft_token_account.as_ref(),
+            &self.user_subscription,
+        )?;
 
         msg!(
-            "Unlocked {} lamports from subscription. Certificate NFT burned: {}",
+            "Unlocked {} lamports from subscription. Certificate NFT revoked: {}",
             locked_amount_for_subscription,
             self.certificate_nft_mint.key()
         );
 
-        // Check if less than one month has passed since last payment for prorated access
-        if let Some(last_payment) = user_subscription.last_payment_at {
-            let time_since_payment = current_time - last_payment;
-            if time_since_payment < billing_period_seconds {
-                msg!(
-                    "User retains access until next billing cycle ({} days remaining)",
-                    (billing_period_seconds - time_since_payment) / seconds_per_day
-                );
-            }
-        } else {
-            // First billing period - user retains access until next payment would be due
-            let time_until_next_payment = user_subscription.next_payment_due - current_time;
-            if time_until_next_payment > 0 {
-                msg!(
-                    "User retains access until next billing cycle ({} days remaining)",
-                    time_until_next_payment / seconds_per_day
-                );
-            }
+        // The user keeps access for the period already paid; the keeper finalises the
+        // cancellation once `access_until` has passed
+        if !was_paused && self.user_subscription.access_until > current_time {
+            self.user_subscription.cancel_at_period_end = true;
+            self.user_subscription.unsubscribed_at = Some(current_time);
+
+            msg!(
+                "User {} cancelled service '{}' at period end: access retained until {} ({} days remaining)",
+                self.user.key(),
+                self.subscription_service.name,
+                self.user_subscription.access_until,
+                (self.user_subscription.access_until - current_time) / seconds_per_day
+            );
+
+            return Ok(());
         }
 
+        // Paused, or nothing left of the paid period: deactivate right away
+        Self::deactivate_subscription(
+            &mut self.user_account,
+            &mut self.user_subscription,
+            &mut self.subscription_service,
+            &mut self.provider_account,
+            current_time,
+        )?;
+
+        msg!(
+            "User {} successfully unsubscribed from service '{}' (Provider: {})",
+            self.user.key(),
+            self.subscription_service.name,
+            self.user_subscription.provider
+        );
+
         Ok(())
     }
 
-    /// Get SOL/USD price from Pyth Network - REAL IMPLEMENTATION
-    fn get_sol_usd_price_from_pyth(price_feed_account: &AccountInfo) -> Result<u64> {
-        // Load price feed from Pyth account using the correct API
-        let price_feed = SolanaPriceAccount::account_info_to_feed(price_feed_account)
-            .map_err(|_| ErrorCode::InvalidPriceFeed)?;
-
-        // Get current price with staleness check
-        let current_time = Clock::get()?.unix_timestamp;
-        let max_age = 3600; // 1 hour in seconds
+    /// Turn a subscription inactive and update every counter that tracks it
+    pub(crate) fn deactivate_subscription(
+        user_account: &mut User,
+        user_subscription: &mut UserSubscription,
+        subscription_service: &mut SubscriptionService,
+        provider_account: &mut Provider,
+        current_time: i64,
+    ) -> Result<()> {
+        user_account.active_subscriptions = user_account
+            .active_subscriptions
+            .checked_sub(1)
+            .ok_or(ErrorCode::LockedSolMismatch)?;
 
-        let price = price_feed
-            .get_price_no_older_than(current_time, max_age)
-            .ok_or(ErrorCode::PriceNotAvailable)?;
+        user_subscription.is_active = false;
+        user_subscription.cancel_at_period_end = false;
+        if user_subscription.unsubscribed_at.is_none() {
+            user_subscription.unsubscribed_at = Some(current_time);
+        }
 
-        // Validate price data
-        require!(price.price > 0, ErrorCode::InvalidPrice);
+        subscription_service.current_subscribers =
+            subscription_service.current_subscribers.saturating_sub(1);
+        provider_account.total_subscribers = provider_account.total_subscribers.saturating_sub(1);
 
-        msg!(
-            "Pyth price data: price={}, conf={}, expo={}, timestamp={}",
-            price.price,
-            price.conf,
-            price.expo,
-            price.publish_time
-        );
+        Ok(())
+    }
 
-        // Convert price to USD cents
-        // Pyth SOL/USD typically has exponent of -8, meaning price is in units of 10^-8 USD
-        let price_cents = if price.expo >= 0 {
-            // Positive exponent: multiply
-            (price.price as u64)
-                .checked_mul(10_u64.pow(price.expo as u32))
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_mul(100) // Convert to cents
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-        } else {
-            // Negative exponent: divide
-            let divisor = 10_u64.pow((-price.expo) as u32);
-            (price.price as u64)
-                .checked_mul(100) // Convert to cents first
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_div(divisor)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-        };
-
-        // Validate reasonable price range ($10 - $1000 per SOL)
+    /// Burn a certificate the holder never burned, so it stops vouching for a subscription
+    /// that has ended. The subscription PDA burns it as the mint's permanent delegate;
+    /// certificates minted before the delegate was set are frozen instead.
+    pub(crate) fn revoke_certificate(
+        token_program: &Program<'info, Token2022>,
+        certificate_nft_mint: &InterfaceAccount<'info, Mint>,
+        certificate_nft_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        user_subscription: &Account<'info, UserSubscription>,
+    ) -> Result<()> {
+        // A burned certificate has nothing left to revoke
+        if certificate_nft_mint.supply == 0 {
+            return Ok(());
+        }
+        let certificate_nft_token_account =
+            certificate_nft_token_account.ok_or(ErrorCode::InvalidCertificateAccount)?;
         require!(
-            price_cents >= 1000 && price_cents <= 100000,
-            ErrorCode::InvalidPrice
+            certificate_nft_token_account.amount > 0,
+            ErrorCode::InvalidCertificateAccount
         );
 
-        msg!(
-            "Real SOL/USD price from Pyth: ${:.2} (account: {})",
-            price_cents as f64 / 100.0,
-            price_feed_account.key()
-        );
-
-        Ok(price_cents)
-    }
-
-    /// Convert USD cents to SOL lamports using real Pyth price
-    fn convert_usd_to_sol_lamports(usd_cents: u64, sol_usd_price_cents: u64) -> Result<u64> {
-        // Calculate SOL lamports needed for the USD amount
-        // usd_cents / (sol_usd_price_cents / 100) * LAMPORTS_PER_SOL
-        // = (usd_cents * 100 * LAMPORTS_PER_SOL) / sol_usd_price_cents
-
-        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let service_id_bytes = user_subscription.service_id.to_le_bytes();
+        let subscription_seeds: &[&[&[u8]]] = &[&[
+            USER_SUBSCRIPTION_SEED.as_bytes(),
+            user_subscription.user.as_ref(),
+            user_subscription.provider.as_ref(),
+            service_id_bytes.as_ref(),
+            &[user_subscription.bumps],
+        ]];
+
+        let permanent_delegate =
+            get_mint_extension_data::<PermanentDelegate>(&certificate_nft_mint.to_account_info())
+                .ok()
+                .and_then(|extension| Option::<Pubkey>::from(extension.delegate));
+        if permanent_delegate != Some(user_subscription.key()) {
+            if !certificate_nft_token_account.is_frozen() {
+                freeze_account(CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    FreezeAccount {
+                        account: certificate_nft_token_account.to_account_info(),
+                        mint: certificate_nft_mint.to_account_info(),
+                        authority: user_subscription.to_account_info(),
+                    },
+                    subscription_seeds,
+                ))?;
+            }
 
-        let numerator = usd_cents
-            .checked_mul(100)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_mul(LAMPORTS_PER_SOL)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+            msg!(
+                "Certificate {} frozen in {}",
+                certificate_nft_mint.key(),
+                certificate_nft_token_account.key()
+            );
+            return Ok(());
+        }
 
-        let sol_lamports = numerator
-            .checked_div(sol_usd_price_cents)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // Frozen balances cannot be burned
+        if certificate_nft_token_account.is_frozen() {
+            thaw_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                ThawAccount {
+                    account: certificate_nft_token_account.to_account_info(),
+                    mint: certificate_nft_mint.to_account_info(),
+                    authority: user_subscription.to_account_info(),
+                },
+                subscription_seeds,
+            ))?;
+        }
+        burn(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Burn {
+                    mint: certificate_nft_mint.to_account_info(),
+                    from: certificate_nft_token_account.to_account_info(),
+                    authority: user_subscription.to_account_info(),
+                },
+                subscription_seeds,
+            ),
+            certificate_nft_token_account.amount,
+        )?;
 
         msg!(
-            "Converting ${:.2} to {} lamports at ${:.2}/SOL",
-            usd_cents as f64 / 100.0,
-            sol_lamports,
-            sol_usd_price_cents as f64 / 100.0
+            "Certificate {} burned from {}",
+            certificate_nft_mint.key(),
+            certificate_nft_token_account.key()
         );
 
-        Ok(sol_lamports)
+        Ok(())
     }
 }
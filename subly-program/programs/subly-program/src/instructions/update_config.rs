@@ -0,0 +1,274 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Authority-only instruction to change the protocol configuration written by `initialize`
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    /// Apply every provided value; `None` leaves the current setting untouched
+    pub fn update_config(
+        &mut self,
+        protocol_fee_bps: Option<u16>,
+        sol_usd_price_feed: Option<Pubkey>,
+        jito_stake_pool: Option<Pubkey>,
+        jito_sol_mint: Option<Pubkey>,
+        spl_stake_pool_program: Option<Pubkey>,
+        usdc_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        let authority = self.authority.key();
+        let current_time = Clock::get()?.unix_timestamp;
+        let global_state = &mut self.global_state;
+
+        if let Some(new_fee_bps) = protocol_fee_bps {
+            require!(
+                new_fee_bps <= MAX_PROTOCOL_FEE_BPS,
+                ErrorCode::InvalidProtocolFee
+            );
+
+            let old_fee_bps = global_state.protocol_fee_bps;
+            global_state.protocol_fee_bps = new_fee_bps;
+
+            emit!(ProtocolFeeUpdated {
+                authority,
+                old_fee_bps,
+                new_fee_bps,
+                timestamp: current_time,
+            });
+            msg!(
+                "Protocol fee updated: {} -> {} bps",
+                old_fee_bps,
+                new_fee_bps
+            );
+        }
+
+        if let Some(new_price_feed) = sol_usd_price_feed {
+            require!(
+                new_price_feed != Pubkey::default(),
+                ErrorCode::InvalidPriceFeed
+            );
+
+            let old_price_feed = global_state.sol_usd_price_feed;
+            global_state.sol_usd_price_feed = new_price_feed;
+
+            emit!(PriceFeedUpdated {
+                authority,
+                old_price_feed,
+                new_price_feed,
+                timestamp: current_time,
+            });
+            msg!(
+                "Pyth SOL/USD price feed updated: {} -> {}",
+                old_price_feed,
+                new_price_feed
+            );
+        }
+
+        // The stake pool, its mint and its program are swapped as one unit
+        if jito_stake_pool.is_some() || jito_sol_mint.is_some() || spl_stake_pool_program.is_some()
+        {
+            let old_stake_pool = global_state.jito_stake_pool;
+            let old_jito_sol_mint = global_state.jito_sol_mint;
+            let old_stake_pool_program = global_state.spl_stake_pool_program;
+
+            global_state.jito_stake_pool = jito_stake_pool.unwrap_or(old_stake_pool);
+            global_state.jito_sol_mint = jito_sol_mint.unwrap_or(old_jito_sol_mint);
+            global_state.spl_stake_pool_program =
+                spl_stake_pool_program.unwrap_or(old_stake_pool_program);
+
+            emit!(StakePoolUpdated {
+                authority,
+                old_stake_pool,
+                new_stake_pool: global_state.jito_stake_pool,
+                old_jito_sol_mint,
+                new_jito_sol_mint: global_state.jito_sol_mint,
+                old_stake_pool_program,
+                new_stake_pool_program: global_state.spl_stake_pool_program,
+                timestamp: current_time,
+            });
+            msg!(
+                "Jito config updated - Pool: {}, Mint: {}, Program: {}",
+                global_state.jito_stake_pool,
+                global_state.jito_sol_mint,
+                global_state.spl_stake_pool_program
+            );
+        }
+
+        if let Some(new_usdc_mint) = usdc_mint {
+            require!(
+                new_usdc_mint != Pubkey::default(),
+                ErrorCode::InvalidUsdcMint
+            );
+
+            let old_usdc_mint = global_state.usdc_mint;
+            global_state.usdc_mint = new_usdc_mint;
+
+            emit!(UsdcMintUpdated {
+                authority,
+                old_usdc_mint,
+                new_usdc_mint,
+                timestamp: current_time,
+            });
+            msg!("USDC mint updated: {} -> {}", old_usdc_mint, new_usdc_mint);
+        }
+
+        Ok(())
+    }
+
+    /// Point the treasury swap adapter at a new AMM program and set its slippage tolerance
+    pub fn update_swap_config(
+        &mut self,
+        swap_program: Pubkey,
+        max_swap_slippage_bps: u16,
+    ) -> Result<()> {
+        require!(
+            max_swap_slippage_bps <= MAX_SWAP_SLIPPAGE_BPS,
+            ErrorCode::InvalidSlippage
+        );
+
+        let global_state = &mut self.global_state;
+        let old_swap_program = global_state.swap_program;
+        global_state.swap_program = swap_program;
+        global_state.max_swap_slippage_bps = max_swap_slippage_bps;
+
+        emit!(SwapConfigUpdated {
+            authority: self.authority.key(),
+            old_swap_program,
+            new_swap_program: swap_program,
+            max_swap_slippage_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Swap config updated - Program: {} -> {}, Max slippage: {} bps",
+            old_swap_program,
+            swap_program,
+            max_swap_slippage_bps
+        );
+
+        Ok(())
+    }
+
+    /// Configure the payment crank: who may execute payments and how keepers are tipped
+    pub fn update_keeper_config(
+        &mut self,
+        permissionless_payments: bool,
+        keeper_reward_bps: u16,
+        keeper_reward_lamports: u64,
+    ) -> Result<()> {
+        require!(
+            keeper_reward_bps <= MAX_KEEPER_REWARD_BPS,
+            ErrorCode::InvalidKeeperReward
+        );
+
+        let global_state = &mut self.global_state;
+        global_state.permissionless_payments = permissionless_payments;
+        global_state.keeper_reward_bps = keeper_reward_bps;
+        global_state.keeper_reward_lamports = keeper_reward_lamports;
+
+        emit!(KeeperConfigUpdated {
+            authority: self.authority.key(),
+            permissionless_payments,
+            keeper_reward_bps,
+            keeper_reward_lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Keeper config updated - Permissionless: {}, Reward: {} bps + {} lamports",
+            permissionless_payments,
+            keeper_reward_bps,
+            keeper_reward_lamports
+        );
+
+        Ok(())
+    }
+
+    pub fn update_dunning_config(
+        &mut self,
+        grace_period_days: u64,
+        payment_retry_interval_hours: u64,
+    ) -> Result<()> {
+        require!(
+            grace_period_days <= MAX_GRACE_PERIOD_DAYS,
+            ErrorCode::InvalidDunningConfig
+        );
+        require!(
+            payment_retry_interval_hours > 0,
+            ErrorCode::InvalidDunningConfig
+        );
+
+        let global_state = &mut self.global_state;
+        global_state.grace_period_days = grace_period_days;
+        global_state.payment_retry_interval_hours = payment_retry_interval_hours;
+
+        emit!(DunningConfigUpdated {
+            authority: self.authority.key(),
+            grace_period_days,
+            payment_retry_interval_hours,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Dunning config updated - Grace period: {} days, Retry every {} hours",
+            grace_period_days,
+            payment_retry_interval_hours
+        );
+
+        Ok(())
+    }
+
+    pub fn update_price_change_config(&mut self, min_price_change_notice_days: u64) -> Result<()> {
+        require!(
+            min_price_change_notice_days <= MAX_PRICE_CHANGE_NOTICE_DAYS,
+            ErrorCode::InvalidPriceChangeNotice
+        );
+
+        self.global_state.min_price_change_notice_days = min_price_change_notice_days;
+
+        emit!(PriceChangeConfigUpdated {
+            authority: self.authority.key(),
+            min_price_change_notice_days,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Price change config updated - Minimum notice: {} days",
+            min_price_change_notice_days
+        );
+
+        Ok(())
+    }
+
+    pub fn update_nft_metadata_config(&mut self, nft_metadata_base_uri: String) -> Result<()> {
+        require!(
+            nft_metadata_base_uri.len() <= MAX_URL_LENGTH,
+            ErrorCode::UrlTooLong
+        );
+
+        self.global_state.nft_metadata_base_uri = nft_metadata_base_uri.clone();
+
+        emit!(NftMetadataConfigUpdated {
+            authority: self.authority.key(),
+            nft_metadata_base_uri: nft_metadata_base_uri.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "NFT metadata config updated - Base URI: {}",
+            nft_metadata_base_uri
+        );
+
+        Ok(())
+    }
+}
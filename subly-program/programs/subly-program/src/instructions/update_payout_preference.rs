@@ -0,0 +1,51 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Provider chooses whether subscription revenue is settled in USDC or paid in SOL
+#[derive(Accounts)]
+pub struct UpdatePayoutPreference<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump = provider_account.bump,
+        constraint = provider_account.wallet == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub provider_account: Account<'info, Provider>,
+}
+
+impl<'info> UpdatePayoutPreference<'info> {
+    pub fn update_payout_preference(
+        &mut self,
+        payout_preference: PayoutPreference,
+        payout_address: Option<Pubkey>,
+    ) -> Result<()> {
+        // SOL payouts go to the provider wallet unless another address is given
+        let payout_address = payout_address.unwrap_or(self.provider.key());
+        require!(
+            payout_address != Pubkey::default(),
+            ErrorCode::InvalidPayoutAddress
+        );
+
+        let provider_account = &mut self.provider_account;
+        provider_account.payout_preference = payout_preference;
+        provider_account.payout_address = payout_address;
+
+        emit!(ProviderPayoutUpdated {
+            provider: self.provider.key(),
+            payout_preference,
+            payout_address,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Provider {} payout preference set to {:?} (SOL payout address: {})",
+            self.provider.key(),
+            payout_preference,
+            payout_address
+        );
+
+        Ok(())
+    }
+}
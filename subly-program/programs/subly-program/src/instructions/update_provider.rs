@@ -0,0 +1,101 @@
+use crate::{constants::*, error::ErrorCode, events::*, instructions::RegisterProvider, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_metadata_interface::state::Field, token_metadata_update_field, Mint, Token2022,
+    TokenMetadataUpdateField,
+};
+
+/// Provider edits its public profile; the provider NFT's metadata name follows the profile name
+#[derive(Accounts)]
+pub struct UpdateProvider<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump = provider_account.bump,
+        constraint = provider_account.wallet == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        address = provider_account.nft_mint @ ErrorCode::InvalidProviderNft
+    )]
+    pub provider_nft_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateProvider<'info> {
+    pub fn update_provider(
+        &mut self,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+
+        if let Some(name) = name {
+            require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+
+            // The metadata extension grows in place when the new name is longer
+            RegisterProvider::fund_account_growth(
+                &self.provider.to_account_info(),
+                &self.provider_nft_mint.to_account_info(),
+                &self.system_program.to_account_info(),
+                name.len().saturating_sub(self.provider_account.name.len()),
+            )?;
+            let provider_key = self.provider.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                PROVIDER_SEED.as_bytes(),
+                provider_key.as_ref(),
+                &[self.provider_account.bump],
+            ]];
+            token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TokenMetadataUpdateField {
+                        program_id: self.token_program.to_account_info(),
+                        metadata: self.provider_nft_mint.to_account_info(),
+                        update_authority: self.provider_account.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                Field::Name,
+                name.clone(),
+            )?;
+
+            self.provider_account.name = name;
+        }
+
+        let provider_account = &mut self.provider_account;
+        if let Some(description) = description {
+            require!(
+                description.len() <= MAX_DESCRIPTION_LENGTH,
+                ErrorCode::DescriptionTooLong
+            );
+            provider_account.description = description;
+        }
+
+        emit!(ProviderUpdated {
+            provider: self.provider.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Provider {} profile updated: '{}'",
+            self.provider.key(),
+            provider_account.name
+        );
+
+        Ok(())
+    }
+}
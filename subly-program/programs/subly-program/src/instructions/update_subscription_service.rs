@@ -0,0 +1,233 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Provider edits the listing metadata of one of its services
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct UpdateSubscriptionService<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+/// Provider withdraws a listing: no new subscriptions or renewals, existing
+/// subscribers keep access until the end of their paid period
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct DeactivateSubscriptionService<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump = provider_account.bump,
+        constraint = provider_account.wallet == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+/// Provider lists a deactivated service again
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct ReactivateSubscriptionService<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.key().as_ref()],
+        bump = provider_account.bump,
+        constraint = provider_account.wallet == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub provider_account: Account<'info, Provider>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+/// Provider chooses whether new certificates of a service can be transferred
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct UpdateCertificatePolicy<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+impl<'info> UpdateSubscriptionService<'info> {
+    pub fn update_subscription_service(
+        &mut self,
+        service_id: u64,
+        name: Option<String>,
+        description: Option<String>,
+        image_url: Option<String>,
+    ) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+
+        let subscription_service = &mut self.subscription_service;
+
+        if let Some(name) = name {
+            require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+            subscription_service.name = name;
+        }
+        if let Some(description) = description {
+            require!(
+                description.len() <= MAX_DESCRIPTION_LENGTH,
+                ErrorCode::DescriptionTooLong
+            );
+            subscription_service.description = description;
+        }
+        if let Some(image_url) = image_url {
+            require!(image_url.len() <= MAX_URL_LENGTH, ErrorCode::UrlTooLong);
+            subscription_service.image_url = image_url;
+        }
+
+        emit!(SubscriptionServiceUpdated {
+            provider: self.provider.key(),
+            service_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Subscription service {} updated: '{}'",
+            service_id,
+            subscription_service.name
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> DeactivateSubscriptionService<'info> {
+    pub fn deactivate_subscription_service(&mut self, service_id: u64) -> Result<()> {
+        require!(
+            self.subscription_service.is_active,
+            ErrorCode::ServiceNotActive
+        );
+
+        self.subscription_service.is_active = false;
+        self.provider_account.live_service_count =
+            self.provider_account.live_service_count.saturating_sub(1);
+
+        emit!(SubscriptionServiceDeactivated {
+            provider: self.provider.key(),
+            service_id,
+            current_subscribers: self.subscription_service.current_subscribers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Keepers finalise the remaining subscribers once their access ends
+        msg!(
+            "Subscription service {} deactivated with {} subscribers running out their paid period",
+            service_id,
+            self.subscription_service.current_subscribers
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> ReactivateSubscriptionService<'info> {
+    pub fn reactivate_subscription_service(&mut self, service_id: u64) -> Result<()> {
+        require!(!self.global_state.is_paused, ErrorCode::ProtocolPaused);
+        require!(
+            !self.subscription_service.is_active,
+            ErrorCode::ServiceAlreadyActive
+        );
+
+        self.subscription_service.is_active = true;
+        self.provider_account.live_service_count = self
+            .provider_account
+            .live_service_count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(SubscriptionServiceReactivated {
+            provider: self.provider.key(),
+            service_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Subscription service {} reactivated", service_id);
+
+        Ok(())
+    }
+}
+
+impl<'info> UpdateCertificatePolicy<'info> {
+    pub fn update_certificate_policy(
+        &mut self,
+        service_id: u64,
+        non_transferable_certificates: bool,
+    ) -> Result<()> {
+        self.subscription_service.non_transferable_certificates = non_transferable_certificates;
+
+        emit!(CertificatePolicyUpdated {
+            provider: self.provider.key(),
+            service_id,
+            non_transferable_certificates,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Certificates already minted keep the policy they were created with
+        msg!(
+            "Service {} certificates are non-transferable: {}",
+            service_id,
+            non_transferable_certificates
+        );
+
+        Ok(())
+    }
+}
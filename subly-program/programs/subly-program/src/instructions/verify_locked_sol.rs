@@ -0,0 +1,68 @@
+use crate::{constants::*, error::ErrorCode, state::*};
+use anchor_lang::prelude::*;
+
+/// Read-only invariant check: `User.locked_sol` must equal the sum of `locked_sol`
+/// over the user's active subscriptions, all of which are passed in `remaining_accounts`
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct VerifyLockedSol<'info> {
+    #[account(
+        seeds = [USER_SEED.as_bytes(), user.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.wallet == user @ ErrorCode::UnauthorizedUser
+    )]
+    pub user_account: Account<'info, User>,
+}
+
+impl<'info> VerifyLockedSol<'info> {
+    pub fn verify_locked_sol(
+        ctx: Context<'_, '_, 'info, 'info, VerifyLockedSol<'info>>,
+    ) -> Result<()> {
+        let user_account = &ctx.accounts.user_account;
+
+        // Every active subscription must be passed exactly once
+        require!(
+            ctx.remaining_accounts.len() as u64 == user_account.active_subscriptions,
+            ErrorCode::LockedSolMismatch
+        );
+
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut total_locked: u64 = 0;
+
+        for subscription_info in ctx.remaining_accounts.iter() {
+            let user_subscription = Account::<UserSubscription>::try_from(subscription_info)?;
+
+            require!(
+                user_subscription.user == user_account.wallet,
+                ErrorCode::UnauthorizedUser
+            );
+            require!(
+                user_subscription.is_active,
+                ErrorCode::SubscriptionNotActive
+            );
+            require!(
+                !seen.contains(subscription_info.key),
+                ErrorCode::LockedSolMismatch
+            );
+            seen.push(subscription_info.key());
+
+            total_locked = total_locked
+                .checked_add(user_subscription.locked_sol)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+
+        require!(
+            total_locked == user_account.locked_sol,
+            ErrorCode::LockedSolMismatch
+        );
+
+        msg!(
+            "Locked SOL invariant holds for user {}: {} SOL across {} active subscriptions",
+            user_account.wallet,
+            total_locked as f64 / 1_000_000_000.0,
+            user_account.active_subscriptions
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{constants::*, error::ErrorCode, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// The authority or the verifier marks a provider as verified
+#[derive(Accounts)]
+#[instruction(provider: Pubkey)]
+pub struct VerifyProvider<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == signer.key()
+            || global_state.verifier == Some(signer.key()) @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.as_ref()],
+        bump = provider_account.bump
+    )]
+    pub provider_account: Account<'info, Provider>,
+}
+
+/// The authority or the verifier withdraws a provider's verification
+#[derive(Accounts)]
+#[instruction(provider: Pubkey)]
+pub struct RevokeProviderVerification<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == signer.key()
+            || global_state.verifier == Some(signer.key()) @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [PROVIDER_SEED.as_bytes(), provider.as_ref()],
+        bump = provider_account.bump
+    )]
+    pub provider_account: Account<'info, Provider>,
+}
+
+/// Authority-only instruction to set or clear the provider verifier key
+#[derive(Accounts)]
+pub struct SetVerifier<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.authority == authority.key() @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Provider decides whether a service is listed before the provider is verified
+#[derive(Accounts)]
+#[instruction(service_id: u64)]
+pub struct UpdateVerificationRequirement<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SUBSCRIPTION_SERVICE_SEED.as_bytes(),
+            provider.key().as_ref(),
+            &service_id.to_le_bytes(),
+        ],
+        bump = subscription_service.bumps,
+        constraint = subscription_service.provider == provider.key() @ ErrorCode::UnauthorizedProvider
+    )]
+    pub subscription_service: Account<'info, SubscriptionService>,
+}
+
+impl<'info> VerifyProvider<'info> {
+    pub fn verify_provider(&mut self, provider: Pubkey) -> Result<()> {
+        require!(
+            !self.provider_account.is_verified,
+            ErrorCode::ProviderAlreadyVerified
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        self.provider_account.is_verified = true;
+        self.provider_account.verified_at = Some(current_time);
+
+        emit!(ProviderVerified {
+            provider,
+            verified_by: self.signer.key(),
+            timestamp: current_time,
+        });
+
+        msg!("Provider {} verified by {}", provider, self.signer.key());
+
+        Ok(())
+    }
+}
+
+impl<'info> RevokeProviderVerification<'info> {
+    pub fn revoke_provider_verification(&mut self, provider: Pubkey) -> Result<()> {
+        require!(
+            self.provider_account.is_verified,
+            ErrorCode::ProviderNotVerified
+        );
+
+        self.provider_account.is_verified = false;
+        self.provider_account.verified_at = None;
+
+        emit!(ProviderVerificationRevoked {
+            provider,
+            revoked_by: self.signer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Verification of provider {} revoked by {}",
+            provider,
+            self.signer.key()
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> SetVerifier<'info> {
+    pub fn set_verifier(&mut self, verifier: Option<Pubkey>) -> Result<()> {
+        let old_verifier = self.global_state.verifier;
+        self.global_state.verifier = verifier;
+
+        emit!(VerifierUpdated {
+            authority: self.authority.key(),
+            old_verifier,
+            new_verifier: verifier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Provider verifier updated: {:?} -> {:?}",
+            old_verifier,
+            verifier
+        );
+
+        Ok(())
+    }
+}
+
+impl<'info> UpdateVerificationRequirement<'info> {
+    pub fn update_verification_requirement(
+        &mut self,
+        service_id: u64,
+        requires_verified_provider: bool,
+    ) -> Result<()> {
+        self.subscription_service.requires_verified_provider = requires_verified_provider;
+
+        emit!(VerificationRequirementUpdated {
+            provider: self.provider.key(),
+            service_id,
+            requires_verified_provider,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Service {} requires a verified provider: {}",
+            service_id,
+            requires_verified_provider
+        );
+
+        Ok(())
+    }
+}
@@ -96,6 +96,7 @@ pub struct Withdraw<'info> {
 
 impl<'info> Withdraw<'info> {
     pub fn withdraw(&mut self, amount: u64, _jito_apy_bps: u16, bumps: &WithdrawBumps) -> Result<()> {
+        // No `is_paused` check on purpose: users must always be able to exit
         require!(amount > 0, ErrorCode::InvalidAmount);
         require!(
             self.user_account.deposited_sol >= amount,
@@ -105,8 +106,7 @@ impl<'info> Withdraw<'info> {
         // Calculate available balance (deposited - locked for subscriptions)
         let available_balance = self.user_account
             .deposited_sol
-            .checked_sub(self.user_account.locked_sol)
-            .unwrap_or(0);
+            .saturating_sub(self.user_account.locked_sol);
 
         require!(
             available_balance >= amount,
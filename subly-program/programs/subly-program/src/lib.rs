@@ -1,11 +1,17 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
+pub mod swap_adapter;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -33,6 +39,98 @@ pub mod subly_program {
         )
     }
 
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        protocol_fee_bps: Option<u16>,
+        sol_usd_price_feed: Option<Pubkey>,
+        jito_stake_pool: Option<Pubkey>,
+        jito_sol_mint: Option<Pubkey>,
+        spl_stake_pool_program: Option<Pubkey>,
+        usdc_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.update_config(
+            protocol_fee_bps,
+            sol_usd_price_feed,
+            jito_stake_pool,
+            jito_sol_mint,
+            spl_stake_pool_program,
+            usdc_mint,
+        )
+    }
+
+    pub fn update_keeper_config(
+        ctx: Context<UpdateConfig>,
+        permissionless_payments: bool,
+        keeper_reward_bps: u16,
+        keeper_reward_lamports: u64,
+    ) -> Result<()> {
+        ctx.accounts.update_keeper_config(
+            permissionless_payments,
+            keeper_reward_bps,
+            keeper_reward_lamports,
+        )
+    }
+
+    pub fn update_dunning_config(
+        ctx: Context<UpdateConfig>,
+        grace_period_days: u64,
+        payment_retry_interval_hours: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_dunning_config(grace_period_days, payment_retry_interval_hours)
+    }
+
+    pub fn update_price_change_config(
+        ctx: Context<UpdateConfig>,
+        min_price_change_notice_days: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_price_change_config(min_price_change_notice_days)
+    }
+
+    pub fn update_nft_metadata_config(
+        ctx: Context<UpdateConfig>,
+        nft_metadata_base_uri: String,
+    ) -> Result<()> {
+        ctx.accounts.update_nft_metadata_config(nft_metadata_base_uri)
+    }
+
+    pub fn update_swap_config(
+        ctx: Context<UpdateConfig>,
+        swap_program: Pubkey,
+        max_swap_slippage_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_swap_config(swap_program, max_swap_slippage_bps)
+    }
+
+    pub fn pause_protocol(ctx: Context<PauseProtocol>) -> Result<()> {
+        ctx.accounts.pause_protocol()
+    }
+
+    pub fn unpause_protocol(ctx: Context<UnpauseProtocol>) -> Result<()> {
+        ctx.accounts.unpause_protocol()
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.set_guardian(guardian)
+    }
+
+    pub fn set_verifier(ctx: Context<SetVerifier>, verifier: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.set_verifier(verifier)
+    }
+
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.propose_authority(new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        ctx.accounts.accept_authority()
+    }
+
     pub fn check_subscribable_services<'info>(
         ctx: Context<'_, '_, '_, 'info, CheckSubscribableServices<'info>>,
         jito_apy_bps: u16, // Jito APY in basis points (e.g., 700 = 7%)
@@ -57,6 +155,50 @@ pub mod subly_program {
             .register_provider(name, description, &ctx.bumps)
     }
 
+    pub fn update_payout_preference(
+        ctx: Context<UpdatePayoutPreference>,
+        payout_preference: PayoutPreference,
+        payout_address: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_payout_preference(payout_preference, payout_address)
+    }
+
+    pub fn verify_provider(ctx: Context<VerifyProvider>, provider: Pubkey) -> Result<()> {
+        ctx.accounts.verify_provider(provider)
+    }
+
+    pub fn revoke_provider_verification(
+        ctx: Context<RevokeProviderVerification>,
+        provider: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.revoke_provider_verification(provider)
+    }
+
+    pub fn update_verification_requirement(
+        ctx: Context<UpdateVerificationRequirement>,
+        service_id: u64,
+        requires_verified_provider: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_verification_requirement(service_id, requires_verified_provider)
+    }
+
+    pub fn update_provider(
+        ctx: Context<UpdateProvider>,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<()> {
+        ctx.accounts.update_provider(name, description)
+    }
+
+    pub fn deregister_provider<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeregisterProvider<'info>>,
+    ) -> Result<()> {
+        DeregisterProvider::deregister_provider(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn register_subscription_service(
         ctx: Context<RegisterSubscriptionService>,
         name: String,
@@ -64,6 +206,8 @@ pub mod subly_program {
         fee_usd: u64,
         billing_frequency_days: u64,
         image_url: String,
+        trial_days: u64,
+        charge_first_period: bool,
     ) -> Result<()> {
         ctx.accounts.register_subscription_service(
             name,
@@ -71,10 +215,79 @@ pub mod subly_program {
             fee_usd,
             billing_frequency_days,
             image_url,
+            trial_days,
+            charge_first_period,
             &ctx.bumps,
         )
     }
 
+    pub fn migrate_global_state(ctx: Context<MigrateGlobalState>) -> Result<()> {
+        ctx.accounts.migrate_global_state(ctx.program_id)
+    }
+
+    pub fn migrate_provider_services<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateProviderServices<'info>>,
+    ) -> Result<()> {
+        MigrateProviderServices::migrate_provider_services(ctx)
+    }
+
+    pub fn update_subscription_service(
+        ctx: Context<UpdateSubscriptionService>,
+        service_id: u64,
+        name: Option<String>,
+        description: Option<String>,
+        image_url: Option<String>,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_subscription_service(service_id, name, description, image_url)
+    }
+
+    pub fn schedule_price_change(
+        ctx: Context<SchedulePriceChange>,
+        service_id: u64,
+        new_fee_usd: u64,
+        effective_at: i64,
+        allow_grandfathering: bool,
+    ) -> Result<()> {
+        ctx.accounts.schedule_price_change(
+            service_id,
+            new_fee_usd,
+            effective_at,
+            allow_grandfathering,
+        )
+    }
+
+    pub fn claim_grandfathered_price(
+        ctx: Context<ClaimGrandfatheredPrice>,
+        _provider: Pubkey,
+        _service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.claim_grandfathered_price()
+    }
+
+    pub fn deactivate_subscription_service(
+        ctx: Context<DeactivateSubscriptionService>,
+        service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.deactivate_subscription_service(service_id)
+    }
+
+    pub fn reactivate_subscription_service(
+        ctx: Context<ReactivateSubscriptionService>,
+        service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.reactivate_subscription_service(service_id)
+    }
+
+    pub fn update_certificate_policy(
+        ctx: Context<UpdateCertificatePolicy>,
+        service_id: u64,
+        non_transferable_certificates: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_certificate_policy(service_id, non_transferable_certificates)
+    }
+
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         ctx.accounts.deposit(amount, &ctx.bumps)
     }
@@ -95,6 +308,40 @@ pub mod subly_program {
             .subscribe_to_service(provider, service_id, &ctx.bumps)
     }
 
+    pub fn pause_subscription(
+        ctx: Context<PauseSubscription>,
+        _provider: Pubkey,
+        _service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.pause_subscription()
+    }
+
+    pub fn resume_subscription(
+        ctx: Context<ResumeSubscription>,
+        _provider: Pubkey,
+        _service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.resume_subscription()
+    }
+
+    pub fn change_plan(
+        ctx: Context<ChangePlan>,
+        provider: Pubkey,
+        from_service_id: u64,
+        to_service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .change_plan(provider, from_service_id, to_service_id, &ctx.bumps)
+    }
+
+    pub fn update_pause_policy(
+        ctx: Context<UpdatePausePolicy>,
+        service_id: u64,
+        pause_policy: PausePolicy,
+    ) -> Result<()> {
+        ctx.accounts.update_pause_policy(service_id, pause_policy)
+    }
+
     pub fn unsubscribe_from_service(
         ctx: Context<UnsubscribeFromService>,
         provider: Pubkey,
@@ -103,6 +350,51 @@ pub mod subly_program {
         ctx.accounts.unsubscribe_from_service(provider, service_id)
     }
 
+    pub fn record_failed_payment(
+        ctx: Context<RecordFailedPayment>,
+        _user: Pubkey,
+        _provider: Pubkey,
+        _service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.record_failed_payment()
+    }
+
+    pub fn finalize_cancellation(
+        ctx: Context<FinalizeCancellation>,
+        _user: Pubkey,
+        _provider: Pubkey,
+        _service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.finalize_cancellation()
+    }
+
+    pub fn close_user_subscription(
+        ctx: Context<CloseUserSubscription>,
+        provider: Pubkey,
+        service_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.close_user_subscription(provider, service_id)
+    }
+
+    pub fn close_payment_records(ctx: Context<ClosePaymentRecords>) -> Result<()> {
+        ctx.accounts.close_payment_records()
+    }
+
+    pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+        ctx.accounts.close_stake_account()
+    }
+
+    pub fn close_user(ctx: Context<CloseUser>) -> Result<()> {
+        ctx.accounts.close_user(&ctx.bumps)
+    }
+
+    pub fn verify_locked_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyLockedSol<'info>>,
+        _user: Pubkey,
+    ) -> Result<()> {
+        VerifyLockedSol::verify_locked_sol(ctx)
+    }
+
     pub fn process_subscription_payments(ctx: Context<ProcessSubscriptionPayments>) -> Result<()> {
         ctx.accounts.process_subscription_payments()
     }
@@ -116,6 +408,16 @@ pub mod subly_program {
         ctx.accounts.execute_payment(&ctx.bumps)
     }
 
+    pub fn execute_subscription_payments_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteSubscriptionPaymentsBatch<'info>>,
+    ) -> Result<BatchPaymentSummary> {
+        ExecuteSubscriptionPaymentsBatch::execute_subscription_payments_batch(ctx)
+    }
+
+    pub fn swap_treasury_sol(ctx: Context<SwapTreasurySol>, sol_amount: u64) -> Result<()> {
+        ctx.accounts.swap_treasury_sol(sol_amount, &ctx.bumps)
+    }
+
     pub fn create_payment_record(
         ctx: Context<CreatePaymentRecord>,
         amount: u64,
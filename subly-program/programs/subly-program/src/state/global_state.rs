@@ -14,8 +14,27 @@ pub struct GlobalState {
     pub sol_usd_price_feed: Pubkey, // SOL/USD price feed account
     // USDC configuration for payments
     pub usdc_mint: Pubkey, // USDC mint address
-    // Global service counter for unique service IDs
+    // Legacy global service counter; service IDs are now allocated per provider
     pub total_services: u64,
     pub last_payment_processed: i64, // Timestamp of last payment processing
     pub bump: u8,
+    // Fields below were appended after the initial layout and must stay at the end
+    pub pending_authority: Option<Pubkey>, // Set by propose_authority, cleared on accept
+    pub guardian: Option<Pubkey>, // Optional key that can only pause the protocol
+    pub verifier: Option<Pubkey>, // Optional key that can verify providers next to the authority
+    // Swap adapter used to convert treasury SOL into USDC
+    pub swap_program: Pubkey,       // Pubkey::default() while unset
+    pub max_swap_slippage_bps: u16, // Allowed shortfall versus the Pyth price
+    // Keeper crank configuration
+    pub permissionless_payments: bool, // Any keeper may execute due payments when true
+    pub keeper_reward_bps: u16,        // Share of the protocol fee paid to the keeper
+    pub keeper_reward_lamports: u64,   // Flat tip added on top, capped at the protocol fee
+    // Dunning configuration for payments the user vault cannot cover
+    pub grace_period_days: u64,            // Past-due time before a subscription is suspended
+    pub payment_retry_interval_hours: u64, // Minimum spacing between recorded failed attempts
+    // Minimum notice providers must give before a new fee applies
+    pub min_price_change_notice_days: u64,
+    // NFT metadata URIs are this base followed by the owning account's address
+    #[max_len(200)]
+    pub nft_metadata_base_uri: String,
 }
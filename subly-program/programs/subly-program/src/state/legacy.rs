@@ -0,0 +1,128 @@
+use super::{GlobalState, PausePolicy, PayoutPreference, Provider, SubscriptionService};
+use crate::constants::*;
+use anchor_lang::prelude::*;
+
+// Frozen account layouts of the initial release. Only read by migrate_global_state and
+// migrate_provider_services; never change these fields. User, UserSubscription and
+// StakeAccount have no legacy layout: the initial release allocated them without the
+// discriminator, so none could ever be written.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyGlobalState {
+    pub authority: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub is_paused: bool,
+    pub jito_stake_pool: Pubkey,
+    pub jito_sol_mint: Pubkey,
+    pub spl_stake_pool_program: Pubkey,
+    pub sol_usd_price_feed: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub total_services: u64,
+    pub last_payment_processed: i64,
+    pub bump: u8,
+}
+
+impl From<LegacyGlobalState> for GlobalState {
+    // Appended fields take the defaults `initialize` gives a fresh deployment
+    fn from(legacy: LegacyGlobalState) -> Self {
+        GlobalState {
+            authority: legacy.authority,
+            protocol_fee_bps: legacy.protocol_fee_bps,
+            is_paused: legacy.is_paused,
+            jito_stake_pool: legacy.jito_stake_pool,
+            jito_sol_mint: legacy.jito_sol_mint,
+            spl_stake_pool_program: legacy.spl_stake_pool_program,
+            sol_usd_price_feed: legacy.sol_usd_price_feed,
+            usdc_mint: legacy.usdc_mint,
+            total_services: legacy.total_services,
+            last_payment_processed: legacy.last_payment_processed,
+            bump: legacy.bump,
+            pending_authority: None,
+            guardian: None,
+            verifier: None,
+            swap_program: Pubkey::default(),
+            max_swap_slippage_bps: DEFAULT_SWAP_SLIPPAGE_BPS,
+            permissionless_payments: false,
+            keeper_reward_bps: 0,
+            keeper_reward_lamports: 0,
+            grace_period_days: DEFAULT_GRACE_PERIOD_DAYS,
+            payment_retry_interval_hours: DEFAULT_PAYMENT_RETRY_INTERVAL_HOURS,
+            min_price_change_notice_days: DEFAULT_PRICE_CHANGE_NOTICE_DAYS,
+            nft_metadata_base_uri: String::new(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyProvider {
+    pub wallet: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub total_subscribers: u64,
+    pub is_verified: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl LegacyProvider {
+    pub fn into_provider(self, service_count: u64, live_service_count: u64) -> Provider {
+        Provider {
+            wallet: self.wallet,
+            name: self.name,
+            description: self.description,
+            total_subscribers: self.total_subscribers,
+            is_verified: self.is_verified,
+            created_at: self.created_at,
+            bump: self.bump,
+            verified_at: None,
+            nft_mint: Pubkey::default(), // Legacy providers were registered without an NFT
+            payout_preference: PayoutPreference::Usdc,
+            payout_address: self.wallet,
+            service_count,
+            live_service_count,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacySubscriptionService {
+    pub provider: Pubkey,
+    pub service_id: u64,
+    pub name: String,
+    pub description: String,
+    pub fee_usd: u64,
+    pub billing_frequency_days: u64,
+    pub image_url: String,
+    pub current_subscribers: u64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub bumps: u8,
+}
+
+impl From<LegacySubscriptionService> for SubscriptionService {
+    fn from(legacy: LegacySubscriptionService) -> Self {
+        SubscriptionService {
+            provider: legacy.provider,
+            service_id: legacy.service_id,
+            name: legacy.name,
+            description: legacy.description,
+            fee_usd: legacy.fee_usd,
+            billing_frequency_days: legacy.billing_frequency_days,
+            image_url: legacy.image_url,
+            current_subscribers: legacy.current_subscribers,
+            is_active: legacy.is_active,
+            created_at: legacy.created_at,
+            bumps: legacy.bumps,
+            pending_fee_usd: None,
+            price_effective_at: 0,
+            price_change_scheduled_at: 0,
+            allow_grandfathering: false,
+            trial_days: 0,
+            charge_first_period: false,
+            requires_verified_provider: false,
+            pause_policy: PausePolicy::KeepLock,
+            non_transferable_certificates: false,
+            fee_changed_at: 0,
+        }
+    }
+}
@@ -1,15 +1,21 @@
 pub mod global_state;
+pub mod legacy;
 pub mod payment_record;
 pub mod provider;
+pub mod provider_record;
 pub mod stake_account;
 pub mod subscription_service;
+pub mod trial_record;
 pub mod user;
 pub mod user_subscription;
 
 pub use global_state::*;
+pub use legacy::*;
 pub use payment_record::*;
 pub use provider::*;
+pub use provider_record::*;
 pub use stake_account::*;
 pub use subscription_service::*;
+pub use trial_record::*;
 pub use user::*;
 pub use user_subscription::*;
@@ -15,9 +15,16 @@ impl anchor_lang::Space for PaymentType {
 pub struct PaymentRecord {
     pub user: Pubkey,
     pub provider: Pubkey,
+    pub user_subscription: Pubkey,
     pub subscription_id: u64,
-    pub amount: u64, // In lamports
+    pub service_id: u64,
+    pub payment_number: u64, // Subscription's total_payments_made before this charge
+    pub amount: u64,         // In lamports
+    pub amount_usd_cents: u64,
+    pub sol_usd_price: u64,  // SOL/USD price in cents used for the charge
+    pub protocol_fee: u64,   // In lamports
     pub payment_date: i64,
     pub payment_type: PaymentType,
     pub bump: u8,
+    pub rent_payer: Pubkey, // Funded the account and reclaims its rent once the record is closed
 }
@@ -1,5 +1,15 @@
 use anchor_lang::prelude::*;
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PayoutPreference {
+    Usdc, // Settled in USDC from the protocol treasury
+    Sol,  // Paid in SOL straight from the user vault
+}
+
+impl anchor_lang::Space for PayoutPreference {
+    const INIT_SPACE: usize = 1; // 1 byte for enum discriminator
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Provider {
@@ -12,4 +22,11 @@ pub struct Provider {
     pub is_verified: bool,
     pub created_at: i64,
     pub bump: u8,
+    // Fields below were appended after the initial layout and must stay at the end
+    pub verified_at: Option<i64>, // Set by verify_provider, cleared on revocation
+    pub nft_mint: Pubkey, // Program-controlled provider NFT, supply fixed at one
+    pub payout_preference: PayoutPreference,
+    pub payout_address: Pubkey, // Receives SOL payouts, defaults to the provider wallet
+    pub service_count: u64,     // Next service ID; services live at (provider, 0..service_count)
+    pub live_service_count: u64, // Services currently active; must be zero to deregister
 }
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// Keeps the service counter of a deregistered provider. Never closed, so a wallet that
+/// registers again continues after the IDs of its old services instead of colliding with them.
+#[account]
+#[derive(InitSpace)]
+pub struct ProviderRecord {
+    pub wallet: Pubkey,
+    pub service_count: u64, // Provider.service_count at the latest deregistration
+    pub deregistered_at: i64,
+    pub bump: u8,
+}
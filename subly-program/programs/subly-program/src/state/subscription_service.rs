@@ -1,5 +1,15 @@
 use anchor_lang::prelude::*;
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PausePolicy {
+    KeepLock,    // Locked SOL stays reserved while a subscription is paused
+    ReleaseLock, // Locked SOL is released on pause and re-locked on resume
+}
+
+impl anchor_lang::Space for PausePolicy {
+    const INIT_SPACE: usize = 1; // 1 byte for enum discriminator
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct SubscriptionService {
@@ -17,4 +27,15 @@ pub struct SubscriptionService {
     pub is_active: bool,
     pub created_at: i64,
     pub bumps: u8,
+    // Fields below were appended after the initial layout and must stay at the end
+    pub pending_fee_usd: Option<u64>, // Scheduled fee for billing cycles starting at or after price_effective_at
+    pub price_effective_at: i64,
+    pub price_change_scheduled_at: i64,
+    pub allow_grandfathering: bool, // Subscribers from before the schedule may keep fee_usd
+    pub trial_days: u64, // Free days before the first charge, 0 charges immediately
+    pub charge_first_period: bool, // Collect the first period inside subscribe_to_service
+    pub requires_verified_provider: bool, // Hidden from discovery until the provider is verified
+    pub pause_policy: PausePolicy,
+    pub non_transferable_certificates: bool, // Certificates minted with the Token-2022 NonTransferable extension
+    pub fee_changed_at: i64, // price_change_scheduled_at of the change that set fee_usd, 0 for the registered fee
 }
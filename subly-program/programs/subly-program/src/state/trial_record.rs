@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// Marks that a wallet has used the free trial of a service, and numbers its certificates.
+/// Never closed, so closing and re-creating a subscription cannot grant a second trial
+/// or reuse a certificate mint address.
+#[account]
+#[derive(InitSpace)]
+pub struct TrialRecord {
+    pub user: Pubkey,
+    pub subscription_service: Pubkey,
+    pub trial_started_at: Option<i64>, // None until a trial has been granted
+    pub certificates_issued: u64,      // Seeds the next certificate mint of the subscription
+    pub bump: u8,
+}
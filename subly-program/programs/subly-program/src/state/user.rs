@@ -9,4 +9,6 @@ pub struct User {
     pub staked_sol: u64,    // lamports staked for yield generation
     pub created_at: i64,
     pub bump: u8,
+    // Fields below were appended after the initial layout and must stay at the end
+    pub active_subscriptions: u64, // number of active subscriptions holding part of locked_sol
 }
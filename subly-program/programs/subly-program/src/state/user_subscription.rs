@@ -14,4 +14,17 @@ pub struct UserSubscription {
     pub is_active: bool,
     pub unsubscribed_at: Option<i64>,
     pub bumps: u8,
+    // Fields below were appended after the initial layout and must stay at the end
+    pub trial_ends_at: Option<i64>, // End of the free trial, None when subscribed without one
+    pub locked_sol: u64, // lamports of User.locked_sol reserved by this subscription
+    pub cancel_at_period_end: bool, // Cancelled by the user, finalised by the keeper at access_until
+    pub access_until: i64,          // End of the period the user has access to
+    pub failed_payment_attempts: u32, // Failed attempts recorded since the payment became past due
+    pub past_due_since: Option<i64>,  // Due date of the unpaid period, None when up to date
+    pub paused_at: Option<i64>,       // Billing clock is frozen while set
+    pub lock_released_on_pause: u64,  // Lamports released by a ReleaseLock pause, re-locked on resume
+    pub grandfathered_fee_usd: Option<u64>, // Fee kept across a provider price change
+    pub proration_lamports: i64,      // Plan-change adjustment billed (+) or credited (-) on the next payment
+    pub certificate_mint: Pubkey, // Certificate NFT minted at the latest subscribe
+    pub grandfathered_change_at: i64, // price_change_scheduled_at of the change grandfathered_fee_usd was claimed against
 }
@@ -0,0 +1,75 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::instruction::{AccountMeta, Instruction},
+};
+
+/// Swap adapter interface for converting treasury SOL into USDC.
+///
+/// Any AMM-style program configured in `GlobalState.swap_program` must expose an
+/// Anchor-compatible `swap_sol_to_token(amount_in: u64, minimum_amount_out: u64)`
+/// instruction taking these accounts, in order:
+///
+/// 0. `[writable]` pool state
+/// 1. `[writable]` pool SOL reserve
+/// 2. `[writable]` pool token reserve
+/// 3. `[writable, signer]` trader paying SOL (the Subly treasury PDA)
+/// 4. `[writable]` trader token account receiving the output token
+/// 5. `[]` SPL Token program
+/// 6. `[]` System program
+///
+/// `programs/mock-amm` is a constant-product implementation used for local testing.
+pub const SWAP_SOL_TO_TOKEN_DISCRIMINATOR: [u8; 8] = [252, 172, 143, 68, 115, 103, 158, 1]; // sha256("global:swap_sol_to_token")[..8]
+
+pub struct SwapSolToTokenAccounts<'a, 'info> {
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_sol_reserve: &'a AccountInfo<'info>,
+    pub pool_token_reserve: &'a AccountInfo<'info>,
+    pub trader: &'a AccountInfo<'info>,
+    pub trader_token_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// CPI into the configured swap program, signing for the trader with `signer_seeds`
+pub fn swap_sol_to_token<'info>(
+    swap_program: &AccountInfo<'info>,
+    accounts: SwapSolToTokenAccounts<'_, 'info>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = SWAP_SOL_TO_TOKEN_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: swap_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.pool.key(), false),
+            AccountMeta::new(accounts.pool_sol_reserve.key(), false),
+            AccountMeta::new(accounts.pool_token_reserve.key(), false),
+            AccountMeta::new(accounts.trader.key(), true),
+            AccountMeta::new(accounts.trader_token_account.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
+
+    anchor_lang::solana_program::program::invoke_signed(
+        &instruction,
+        &[
+            accounts.pool.clone(),
+            accounts.pool_sol_reserve.clone(),
+            accounts.pool_token_reserve.clone(),
+            accounts.trader.clone(),
+            accounts.trader_token_account.clone(),
+            accounts.token_program.clone(),
+            accounts.system_program.clone(),
+            swap_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}